@@ -0,0 +1,252 @@
+use crate::types::Chord;
+
+#[derive(PartialEq, Debug)]
+pub struct Difficulty {
+    pub span: i32,      // frets between lowest and highest fretted note
+    pub fingers: usize, // fingers needed to fret the chord
+    pub barre: bool,
+    pub stretch: i32,       // biggest fret gap between neighbouring fretted strings
+    pub muted_inner: usize, // muted strings between played strings
+    pub position: i32,      // lowest fretted note
+    pub score: u32,
+    pub grade: u8, // 1 (easy) to 5 (hard)
+}
+
+fn is_fretting_finger(finger: &str) -> bool {
+    finger != "0" && finger != "x"
+}
+
+fn count_fingers(chord: &Chord, fretted: &[(usize, i32)]) -> usize {
+    let mut fingers: Vec<&str> = fretted
+        .iter()
+        .filter_map(|(string, _)| chord.fingers.get(*string).copied())
+        .filter(|finger| is_fretting_finger(finger))
+        .collect();
+    fingers.sort();
+    fingers.dedup();
+
+    if !fingers.is_empty() {
+        return fingers.len();
+    }
+
+    // no fingering given, so assume one finger per note apart from barres
+    let barres = chord.barres.clone().unwrap_or_default();
+    let unbarred = fretted
+        .iter()
+        .filter(|(_, fret)| !barres.contains(fret))
+        .count();
+    let barred = barres
+        .iter()
+        .filter(|barre| fretted.iter().any(|(_, fret)| fret == *barre))
+        .count();
+    unbarred + barred
+}
+
+fn has_barre(chord: &Chord, fretted: &[(usize, i32)]) -> bool {
+    if chord
+        .barres
+        .as_ref()
+        .is_some_and(|barres| !barres.is_empty())
+    {
+        return true;
+    }
+
+    // same finger holding down more than one string
+    fretted.iter().enumerate().any(|(i, (string, _))| {
+        let finger = chord.fingers.get(*string).copied().unwrap_or("x");
+        is_fretting_finger(finger)
+            && finger != "T"
+            && fretted[i + 1..]
+                .iter()
+                .any(|(other, _)| chord.fingers.get(*other) == Some(&finger))
+    })
+}
+
+fn get_grade(score: u32) -> u8 {
+    match score {
+        0..=2 => 1,
+        3..=5 => 2,
+        6..=8 => 3,
+        9..=11 => 4,
+        _ => 5,
+    }
+}
+
+/// Scores how hard a chord is to play, for sorting chords for beginners
+pub fn analyse(chord: &Chord) -> Difficulty {
+    let fretted: Vec<(usize, i32)> = chord
+        .frets
+        .iter()
+        .enumerate()
+        .filter_map(|(string, fret)| fret.fretted().map(|fret| (string, fret)))
+        .collect();
+
+    let position = fretted.iter().map(|(_, fret)| *fret).min().unwrap_or(0);
+    let highest = fretted.iter().map(|(_, fret)| *fret).max().unwrap_or(0);
+    let span = highest - position;
+
+    let stretch = fretted
+        .windows(2)
+        .map(|pair| (pair[0].1 - pair[1].1).abs())
+        .max()
+        .unwrap_or(0);
+
+    let first_played = chord.frets.iter().position(|fret| fret.number().is_some());
+    let last_played = chord.frets.iter().rposition(|fret| fret.number().is_some());
+    let muted_inner = match (first_played, last_played) {
+        (Some(first), Some(last)) => chord.frets[first..=last]
+            .iter()
+            .filter(|fret| fret.number().is_none())
+            .count(),
+        _ => 0,
+    };
+
+    let fingers = count_fingers(chord, &fretted);
+    let barre = has_barre(chord, &fretted);
+    let thumb = chord.fingers.contains(&"T");
+
+    let mut score = 0;
+    score += (span.max(1) - 1) as u32 * 2;
+    score += fingers.saturating_sub(1) as u32;
+    score += if barre { 4 } else { 0 };
+    score += (stretch.max(2) - 2) as u32 * 2;
+    score += muted_inner as u32 * 2;
+    score += if position > 5 { 1 } else { 0 };
+    score += if thumb { 2 } else { 0 };
+
+    Difficulty {
+        span,
+        fingers,
+        barre,
+        stretch,
+        muted_inner,
+        position,
+        score,
+        grade: get_grade(score),
+    }
+}
+
+/// Easiest chords first
+pub fn sort_by_difficulty(chords: &mut [Chord]) {
+    chords.sort_by_cached_key(|chord| analyse(chord).score);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::{analyse, sort_by_difficulty, Difficulty},
+        types::{frets_from_numbers, Chord},
+    };
+
+    #[test]
+    fn should_score_open_chord() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 2, 2, 1, 0, 0]),
+            fingers: vec!["0", "2", "3", "1", "0", "0"],
+            ..Default::default()
+        };
+        assert_eq!(
+            analyse(&chord),
+            Difficulty {
+                span: 1,
+                fingers: 3,
+                barre: false,
+                stretch: 1,
+                muted_inner: 0,
+                position: 1,
+                score: 2,
+                grade: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn should_score_barre_chord() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+            fingers: vec!["1", "3", "4", "2", "1", "1"],
+            ..Default::default()
+        };
+        assert_eq!(
+            analyse(&chord),
+            Difficulty {
+                span: 2,
+                fingers: 4,
+                barre: true,
+                stretch: 2,
+                muted_inner: 0,
+                position: 1,
+                score: 9,
+                grade: 4,
+            }
+        );
+
+        // barre passed in rather than inferred from fingers
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 3, 5, 5, 4, 3]),
+            barres: Some(vec![3]),
+            ..Default::default()
+        };
+        let difficulty = analyse(&chord);
+        assert!(difficulty.barre);
+        assert_eq!(difficulty.fingers, 4);
+    }
+
+    #[test]
+    fn should_score_muted_inner_strings_and_stretches() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[3, -1, 0, 0, 3, 3]),
+            fingers: vec!["2", "x", "0", "0", "3", "4"],
+            ..Default::default()
+        };
+        let difficulty = analyse(&chord);
+        assert_eq!(difficulty.muted_inner, 1);
+        assert_eq!(difficulty.stretch, 0);
+        assert_eq!(difficulty.score, 4);
+        assert_eq!(difficulty.grade, 2);
+
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
+            fingers: vec!["x", "2", "1", "3", "4", "x"],
+            ..Default::default()
+        };
+        let difficulty = analyse(&chord);
+        assert_eq!(difficulty.muted_inner, 0);
+        assert_eq!(difficulty.position, 6);
+        assert_eq!(difficulty.grade, 3);
+
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 3, 8, -1, -1, -1]),
+            fingers: vec!["x", "1", "4", "x", "x", "x"],
+            ..Default::default()
+        };
+        let difficulty = analyse(&chord);
+        assert_eq!(difficulty.stretch, 5);
+        assert_eq!(difficulty.grade, 5);
+    }
+
+    #[test]
+    fn should_sort_easiest_first() {
+        let mut chords = vec![
+            Chord {
+                frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+                fingers: vec!["1", "3", "4", "2", "1", "1"],
+                ..Default::default()
+            },
+            Chord {
+                frets: frets_from_numbers(&[-1, 3, 2, 0, 1, 0]),
+                fingers: vec!["x", "3", "2", "0", "1", "0"],
+                ..Default::default()
+            },
+            Chord {
+                frets: frets_from_numbers(&[0, 2, 2, 0, 0, 0]),
+                fingers: vec!["0", "2", "3", "0", "0", "0"],
+                ..Default::default()
+            },
+        ];
+        sort_by_difficulty(&mut chords);
+        assert_eq!(chords[0].frets, frets_from_numbers(&[0, 2, 2, 0, 0, 0]));
+        assert_eq!(chords[1].frets, frets_from_numbers(&[-1, 3, 2, 0, 1, 0]));
+        assert_eq!(chords[2].frets, frets_from_numbers(&[1, 3, 3, 2, 1, 1]));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{midi::get_midi_notes, types::Chord, utils::get_strum_offsets};
+
+const SAMPLE_RATE: u32 = 44_100;
+const DECAY: f32 = 0.996;
+const FADE_OUT: usize = SAMPLE_RATE as usize / 20; // 50ms
+
+fn get_frequency(note: u8) -> f32 {
+    440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+// xorshift, so the same chord always sounds (and hashes) the same
+fn noise(seed: &mut u32) -> f32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    (*seed as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+/// Karplus-Strong plucked string: a burst of noise fed through a
+/// delay line one period long, averaged on each pass to damp it.
+pub fn pluck(note: u8, length: usize, seed: u32) -> Vec<f32> {
+    let period = (SAMPLE_RATE as f32 / get_frequency(note)).round().max(2.0) as usize;
+    let mut seed = seed.max(1);
+    let mut delay_line: Vec<f32> = (0..period).map(|_| noise(&mut seed)).collect();
+
+    let mut samples = Vec::with_capacity(length);
+    for i in 0..length {
+        let current = i % period;
+        let next = (i + 1) % period;
+        let sample = delay_line[current];
+        delay_line[current] = DECAY * 0.5 * (sample + delay_line[next]);
+        samples.push(sample);
+    }
+    samples
+}
+
+pub fn synthesise(chord: &Chord) -> Vec<i16> {
+    let notes = get_midi_notes(chord);
+    let offsets = get_strum_offsets(&chord.strum, notes.len());
+
+    // ring for one bar of 4/4
+    let length = (SAMPLE_RATE * 4 * 60 / chord.strum.tempo.max(1)) as usize;
+    let mut mix = vec![0f32; length];
+
+    for (note, offset) in notes.iter().zip(offsets.iter()) {
+        let start =
+            (offset.saturating_mul(u64::from(SAMPLE_RATE)) / 1000).min(length as u64) as usize;
+        let string = pluck(*note, length - start, *note as u32);
+        for (mixed, sample) in mix[start..].iter_mut().zip(string.iter()) {
+            *mixed += sample;
+        }
+    }
+
+    let peak = mix.iter().fold(0f32, |peak, sample| peak.max(sample.abs()));
+    let gain = if peak > 0.0 { 0.8 / peak } else { 0.0 };
+    let fade_start = length.saturating_sub(FADE_OUT);
+
+    mix.iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let fade = if i > fade_start {
+                (length - i) as f32 / FADE_OUT as f32
+            } else {
+                1.0
+            };
+            (sample * gain * fade * i16::MAX as f32) as i16
+        })
+        .collect()
+}
+
+/// 16-bit mono PCM
+pub fn generate_wav(chord: &Chord) -> Vec<u8> {
+    let samples = synthesise(chord);
+    let data_length = samples.len() as u32 * 2;
+
+    let mut bytes: Vec<u8> = vec![];
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_length).to_le_bytes());
+    bytes.extend(b"WAVE");
+    bytes.extend(b"fmt ");
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes()); // PCM
+    bytes.extend(1u16.to_le_bytes()); // mono
+    bytes.extend(SAMPLE_RATE.to_le_bytes());
+    bytes.extend((SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    bytes.extend(2u16.to_le_bytes()); // block align
+    bytes.extend(16u16.to_le_bytes()); // bits per sample
+    bytes.extend(b"data");
+    bytes.extend(data_length.to_le_bytes());
+    for sample in samples {
+        bytes.extend(sample.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        audio::{generate_wav, get_frequency, pluck, synthesise},
+        types::{frets_from_numbers, Chord, Strum, StrumDirection},
+    };
+
+    #[test]
+    fn should_get_frequency() {
+        assert_eq!(get_frequency(69), 440.0);
+        assert_eq!(get_frequency(57), 220.0);
+        assert!((get_frequency(40) - 82.41).abs() < 0.01);
+    }
+
+    #[test]
+    fn pluck_should_decay() {
+        let samples = pluck(45, 44_100, 45);
+        let energy = |samples: &[f32]| samples.iter().map(|s| s * s).sum::<f32>();
+        assert!(energy(&samples[..4410]) > energy(&samples[39_690..]) * 10.0);
+    }
+
+    #[test]
+    fn should_ring_for_one_bar() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            ..Default::default()
+        };
+        // 2s at 120bpm
+        assert_eq!(synthesise(&chord).len(), 88_200);
+
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            strum: Strum {
+                tempo: 60,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(synthesise(&chord).len(), 176_400);
+    }
+
+    #[test]
+    fn should_delay_strummed_strings() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, -1, 0]),
+            strum: Strum {
+                direction: StrumDirection::Up,
+                delay: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // only one string so plays straight away
+        assert!(synthesise(&chord)[..100].iter().any(|s| *s != 0));
+
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 0, 0]),
+            strum: Strum {
+                direction: StrumDirection::Down,
+                delay: 100,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let down = synthesise(&chord);
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 0, 0]),
+            ..Default::default()
+        };
+        let block = synthesise(&chord);
+        assert_ne!(down[4410..4500], block[4410..4500]);
+    }
+
+    #[test]
+    fn should_write_wav_header() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            ..Default::default()
+        };
+        let wav = generate_wav(&chord);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav[24..28], 44_100u32.to_le_bytes());
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(wav[40..44], 176_400u32.to_le_bytes());
+        assert_eq!(wav.len(), 44 + 176_400);
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use crate::fingering::detect_barres;
+use crate::types::{frets_from_numbers, Chord, Fret};
+
+/// The open chord a moveable shape comes from
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum CagedShape {
+    C,
+    A,
+    G,
+    E,
+    D,
+}
+
+impl fmt::Display for CagedShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CagedShape::C => "C",
+            CagedShape::A => "A",
+            CagedShape::G => "G",
+            CagedShape::E => "E",
+            CagedShape::D => "D",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// open chord frets, the pitch class of its root, and fingers for the open
+// chord and once it's moved, where the index finger replaces the nut
+type Shape = (
+    CagedShape,
+    [i32; 6],
+    u8,
+    [&'static str; 6],
+    [&'static str; 6],
+);
+
+const MAJOR: [Shape; 5] = [
+    (
+        CagedShape::C,
+        [-1, 3, 2, 0, 1, 0],
+        0,
+        ["x", "3", "2", "0", "1", "0"],
+        ["x", "4", "3", "1", "2", "1"],
+    ),
+    (
+        CagedShape::A,
+        [-1, 0, 2, 2, 2, 0],
+        9,
+        ["x", "0", "1", "2", "3", "0"],
+        ["x", "1", "2", "3", "4", "1"],
+    ),
+    (
+        CagedShape::G,
+        [3, 2, 0, 0, 0, 3],
+        7,
+        ["2", "1", "0", "0", "0", "3"],
+        ["3", "2", "1", "1", "1", "4"],
+    ),
+    (
+        CagedShape::E,
+        [0, 2, 2, 1, 0, 0],
+        4,
+        ["0", "2", "3", "1", "0", "0"],
+        ["1", "3", "4", "2", "1", "1"],
+    ),
+    (
+        CagedShape::D,
+        [-1, -1, 0, 2, 3, 2],
+        2,
+        ["x", "x", "0", "1", "3", "2"],
+        ["x", "x", "1", "2", "4", "3"],
+    ),
+];
+
+const MINOR: [Shape; 5] = [
+    (
+        CagedShape::C,
+        [-1, 3, 1, 0, 1, -1],
+        0,
+        ["x", "3", "1", "0", "2", "x"],
+        ["x", "4", "2", "1", "3", "x"],
+    ),
+    (
+        CagedShape::A,
+        [-1, 0, 2, 2, 1, 0],
+        9,
+        ["x", "0", "2", "3", "1", "0"],
+        ["x", "1", "3", "4", "2", "1"],
+    ),
+    (
+        CagedShape::G,
+        [3, 1, 0, 0, 3, 3],
+        7,
+        ["3", "1", "0", "0", "3", "4"],
+        ["3", "2", "1", "1", "4", "4"],
+    ),
+    (
+        CagedShape::E,
+        [0, 2, 2, 0, 0, 0],
+        4,
+        ["0", "2", "3", "0", "0", "0"],
+        ["1", "3", "4", "1", "1", "1"],
+    ),
+    (
+        CagedShape::D,
+        [-1, -1, 0, 2, 3, 1],
+        2,
+        ["x", "x", "0", "2", "3", "1"],
+        ["x", "x", "1", "3", "4", "2"],
+    ),
+];
+
+/// One of the five shapes of a chord, moved up the neck
+#[derive(PartialEq, Debug)]
+pub struct Caged {
+    pub shape: CagedShape,
+    pub frets: Vec<Fret>,
+    pub fingers: Vec<&'static str>,
+    pub barres: Vec<i32>,
+}
+
+impl Caged {
+    /// Lowest fret played, 0 for the open chord
+    pub fn position(&self) -> i32 {
+        self.frets
+            .iter()
+            .filter_map(|fret| fret.number())
+            .min()
+            .unwrap_or(0)
+    }
+
+    pub fn chord<'a>(&self, title: Option<&'a String>, suffix: Option<&'a String>) -> Chord<'a> {
+        Chord {
+            frets: self.frets.clone(),
+            fingers: self.fingers.clone(),
+            title,
+            suffix,
+            barres: Some(self.barres.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// The C, A, G, E and D shapes of a major or minor chord with this root
+/// (pitch class, C = 0), in order up the neck
+pub fn caged(root: u8, minor: bool) -> Vec<Caged> {
+    let shapes = if minor { MINOR } else { MAJOR };
+    let mut chords: Vec<Caged> = shapes
+        .iter()
+        .map(|(shape, frets, shape_root, open_fingers, moved_fingers)| {
+            let offset = (root as i32 - *shape_root as i32).rem_euclid(12);
+            let moved: Vec<i32> = frets
+                .iter()
+                .map(|fret| if *fret < 0 { *fret } else { fret + offset })
+                .collect();
+            let frets = frets_from_numbers(&moved);
+            let fingers = if offset == 0 {
+                open_fingers.to_vec()
+            } else {
+                moved_fingers.to_vec()
+            };
+            Caged {
+                shape: *shape,
+                barres: detect_barres(&frets, &fingers),
+                frets,
+                fingers,
+            }
+        })
+        .collect();
+    chords.sort_by_key(|chord| chord.position());
+    chords
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        caged::{caged, CagedShape},
+        types::frets_from_numbers,
+    };
+
+    #[test]
+    fn should_move_shapes_up_the_neck() {
+        // G
+        let shapes = caged(7, false);
+        let order: Vec<CagedShape> = shapes.iter().map(|shape| shape.shape).collect();
+        assert_eq!(
+            order,
+            vec![
+                CagedShape::G,
+                CagedShape::E,
+                CagedShape::D,
+                CagedShape::C,
+                CagedShape::A
+            ]
+        );
+        let positions: Vec<i32> = shapes.iter().map(|shape| shape.position()).collect();
+        assert_eq!(positions, vec![0, 3, 5, 7, 10]);
+
+        let e_shape = &shapes[1];
+        assert_eq!(e_shape.frets, frets_from_numbers(&[3, 5, 5, 4, 3, 3]));
+        assert_eq!(e_shape.fingers, vec!["1", "3", "4", "2", "1", "1"]);
+        assert_eq!(e_shape.barres, vec![3]);
+
+        let c_shape = &shapes[3];
+        assert_eq!(c_shape.frets, frets_from_numbers(&[-1, 10, 9, 7, 8, 7]));
+        assert_eq!(c_shape.fingers, vec!["x", "4", "3", "1", "2", "1"]);
+        assert_eq!(c_shape.barres, vec![7]);
+    }
+
+    #[test]
+    fn should_keep_open_shapes() {
+        // C major, the C shape is the open chord
+        let shapes = caged(0, false);
+        assert_eq!(shapes[0].shape, CagedShape::C);
+        assert_eq!(shapes[0].frets, frets_from_numbers(&[-1, 3, 2, 0, 1, 0]));
+        assert_eq!(shapes[0].fingers, vec!["x", "3", "2", "0", "1", "0"]);
+        assert!(shapes[0].barres.is_empty());
+    }
+
+    #[test]
+    fn should_move_minor_shapes() {
+        // A minor
+        let shapes = caged(9, true);
+        let a_shape = shapes
+            .iter()
+            .find(|shape| shape.shape == CagedShape::A)
+            .unwrap();
+        assert_eq!(a_shape.frets, frets_from_numbers(&[-1, 0, 2, 2, 1, 0]));
+
+        let e_shape = shapes
+            .iter()
+            .find(|shape| shape.shape == CagedShape::E)
+            .unwrap();
+        assert_eq!(e_shape.frets, frets_from_numbers(&[5, 7, 7, 5, 5, 5]));
+        assert_eq!(e_shape.fingers, vec!["1", "3", "4", "1", "1", "1"]);
+        assert_eq!(e_shape.barres, vec![5]);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    fingering::settle_fingering,
+    spelling::tuning_names,
+    types::{Chord, Fret},
+};
+
+// open note of each string, low E first, eg "low E", "A", "D" in standard
+// tuning, or "D", "A", "D" in drop D
+fn string_names(chord: &Chord) -> Vec<String> {
+    let mut names: Vec<String> = tuning_names(chord)
+        .iter()
+        .map(|name| spell_accidentals(name))
+        .collect();
+    let last = names.len().saturating_sub(1);
+    if last > 0 && names[0] == names[last] {
+        names[0] = format!("low {}", names[0]);
+        names[last] = format!("high {}", names[last]);
+    }
+    names
+}
+
+fn ordinal(number: i32) -> String {
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", number, suffix)
+}
+
+fn spell_suffix(suffix: &str) -> String {
+    match suffix {
+        "" => String::from(""),
+        "m" | "min" | "-" => String::from(" minor"),
+        "maj" => String::from(" major"),
+        "m7" | "min7" => String::from(" minor 7"),
+        "maj7" => String::from(" major 7"),
+        "dim" | "o" => String::from(" diminished"),
+        "aug" | "+" => String::from(" augmented"),
+        _ => format!(" {}", suffix),
+    }
+}
+
+fn spell_accidentals(name: &str) -> String {
+    name.replace('♭', " flat")
+        .replace('♯', " sharp")
+        .replace('♮', " natural")
+}
+
+/// Chord name for screen readers eg "A minor", "E flat 7"
+pub fn chord_label(chord: &Chord) -> Option<String> {
+    let title = chord.title?;
+    let suffix = chord.suffix.map(|suffix| suffix.as_str()).unwrap_or("");
+    Some(spell_accidentals(&format!(
+        "{}{}",
+        title,
+        spell_suffix(suffix)
+    )))
+}
+
+fn describe_string(
+    string: usize,
+    name: Option<&String>,
+    fret: &Fret,
+    finger: Option<&str>,
+) -> String {
+    let name = name
+        .cloned()
+        .unwrap_or_else(|| format!("string {}", string + 1));
+    let finger = match finger {
+        Some("T") => String::from(" with thumb"),
+        Some(finger) if finger != "x" && finger != "0" => format!(" with finger {}", finger),
+        _ => String::from(""),
+    };
+
+    match fret {
+        Fret::Muted => format!("mute {}", name),
+        Fret::Unplayed => format!("don't play {}", name),
+        Fret::Open => format!("open {}", name),
+        Fret::Fretted(fret) => format!("{} fret {}{}", ordinal(*fret), name, finger),
+        Fret::Optional(0) => format!("optional open {}", name),
+        Fret::Optional(fret) => format!("optional {} fret {}{}", ordinal(*fret), name, finger),
+    }
+}
+
+/// Text description of the chord, for alt text and screen readers eg
+/// "A minor: mute low E, open A, 2nd fret D with finger 2, ...". Missing
+/// fingers and barres are filled in as they are on the diagram.
+pub fn describe(chord: &Chord) -> String {
+    let (fingers, barres) = settle_fingering(chord);
+    description(chord, &fingers, barres.as_deref().unwrap_or_default())
+}
+
+/// Description of a chord whose fingers and barres are the ones drawn
+pub(crate) fn describe_settled(chord: &Chord) -> String {
+    description(
+        chord,
+        &chord.fingers,
+        chord.barres.as_deref().unwrap_or_default(),
+    )
+}
+
+fn description(chord: &Chord, fingers: &[&str], barres: &[i32]) -> String {
+    let names = string_names(chord);
+    let mut parts: Vec<String> = chord
+        .frets
+        .iter()
+        .enumerate()
+        .map(|(string, fret)| {
+            describe_string(
+                string,
+                names.get(string),
+                fret,
+                fingers.get(string).copied(),
+            )
+        })
+        .collect();
+
+    for barre in barres {
+        parts.push(format!("barre across the {} fret", ordinal(*barre)));
+    }
+
+    let label = chord_label(chord).unwrap_or_else(|| String::from("Chord"));
+    format!("{}: {}.", label, parts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        describe::{chord_label, describe, ordinal},
+        types::{frets_from_numbers, Chord, Fret, Tuning},
+    };
+
+    #[test]
+    fn should_get_ordinals() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(21), "21st");
+    }
+
+    #[test]
+    fn should_label_chords() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            ..Default::default()
+        };
+        assert_eq!(chord_label(&chord), Some(String::from("E flat 7")));
+
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            ..Default::default()
+        };
+        assert_eq!(chord_label(&chord), Some(String::from("A minor")));
+
+        assert_eq!(chord_label(&Chord::default()), None);
+    }
+
+    #[test]
+    fn should_describe_chord() {
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            fingers: vec!["x", "0", "2", "3", "1", "0"],
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&chord),
+            "A minor: mute low E, open A, 2nd fret D with finger 2, 2nd fret G with finger 3, 1st fret B with finger 1, open high E."
+        );
+    }
+
+    #[test]
+    fn should_describe_barres_and_markers() {
+        let title = String::from("F");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&chord),
+            "F: 1st fret low E with finger 1, 3rd fret A with finger 3, 3rd fret D with finger 4, 2nd fret G with finger 2, 1st fret B with finger 1, 1st fret high E with finger 1, barre across the 1st fret."
+        );
+
+        let chord = Chord {
+            frets: vec![
+                Fret::Unplayed,
+                Fret::Optional(3),
+                Fret::Fretted(2),
+                Fret::Open,
+                Fret::Fretted(1),
+                Fret::Optional(0),
+            ],
+            fingers: vec!["x", "3", "2", "0", "1", "0"],
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&chord),
+            "Chord: don't play low E, optional 3rd fret A with finger 3, 2nd fret D with finger 2, open G, 1st fret B with finger 1, optional open high E."
+        );
+    }
+
+    #[test]
+    fn should_name_strings_from_tuning() {
+        let title = String::from("D");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[0, 0, 0, 2, 3, 2]),
+            fingers: vec!["0", "0", "0", "1", "3", "2"],
+            tuning: Tuning(vec![38, 45, 50, 55, 59, 64]),
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&chord),
+            "D: open D, open A, open D, 2nd fret G with finger 1, 3rd fret B with finger 3, 2nd fret E with finger 2."
+        );
+
+        // half a step down, on seven strings
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 0, 0, 0, 0, 0]),
+            tuning: Tuning(vec![34, 39, 44, 49, 54, 58, 63]),
+            ..Default::default()
+        };
+        assert_eq!(
+            describe(&chord),
+            "Chord: mute B flat, open E flat, open A flat, open D flat, open G flat, open B flat, open E flat."
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use crate::{
+    types::{Chord, Fret},
+    utils::find_all,
+};
+
+const FINGERS: [&str; 4] = ["1", "2", "3", "4"];
+
+#[derive(PartialEq, Debug)]
+pub struct Fingering {
+    pub fingers: Vec<&'static str>,
+    pub barres: Vec<i32>,
+}
+
+// strings held down by a single finger
+#[derive(Debug)]
+struct Placement {
+    strings: Vec<usize>,
+    fret: i32,
+    thumb: bool,
+}
+
+impl Placement {
+    fn is_single(&self) -> bool {
+        self.strings.len() == 1 && !self.thumb
+    }
+}
+
+// lay a finger across every string at the lowest fret
+fn merge_lowest_barre(frets: &[Fret], placements: &mut Vec<Placement>) {
+    let Some(lowest) = placements.iter().map(|p| p.fret).min() else {
+        return;
+    };
+    let strings = find_all(frets, &lowest);
+    if strings.len() < 2 {
+        return;
+    }
+
+    let first = strings[0];
+    let last = strings[strings.len() - 1];
+    // can't barre over open or muted strings
+    if frets[first..=last]
+        .iter()
+        .any(|fret| fret.fretted().is_none())
+    {
+        return;
+    }
+
+    placements.retain(|p| !(p.fret == lowest && strings.contains(&p.strings[0])));
+    placements.push(Placement {
+        strings,
+        fret: lowest,
+        thumb: false,
+    });
+}
+
+// partial barre over the longest run of neighbouring strings on one fret
+fn merge_longest_run(placements: &mut Vec<Placement>) -> bool {
+    let mut singles: Vec<(usize, i32)> = placements
+        .iter()
+        .filter(|p| p.is_single())
+        .map(|p| (p.strings[0], p.fret))
+        .collect();
+    singles.sort();
+
+    let mut longest: Vec<(usize, i32)> = vec![];
+    let mut run: Vec<(usize, i32)> = vec![];
+    for note in singles {
+        match run.last() {
+            Some((string, fret)) if *string + 1 == note.0 && *fret == note.1 => run.push(note),
+            _ => run = vec![note],
+        }
+        if run.len() > longest.len() {
+            longest = run.clone();
+        }
+    }
+    if longest.len() < 2 {
+        return false;
+    }
+
+    let strings: Vec<usize> = longest.iter().map(|(string, _)| *string).collect();
+    placements.retain(|p| !(p.is_single() && strings.contains(&p.strings[0])));
+    placements.push(Placement {
+        strings,
+        fret: longest[0].1,
+        thumb: false,
+    });
+    true
+}
+
+/// Works out which finger to use for each string from the frets, using
+/// barres and the thumb when there are more notes than fingers. `None` if
+/// the shape still needs more than four fingers.
+pub fn suggest_fingering(frets: &[Fret]) -> Option<Fingering> {
+    let mut placements: Vec<Placement> = frets
+        .iter()
+        .enumerate()
+        .filter_map(|(string, fret)| {
+            fret.fretted().map(|fret| Placement {
+                strings: vec![string],
+                fret,
+                thumb: false,
+            })
+        })
+        .collect();
+
+    if placements.len() > FINGERS.len() {
+        merge_lowest_barre(frets, &mut placements);
+    }
+    while placements.len() > FINGERS.len() {
+        if !merge_longest_run(&mut placements) {
+            break;
+        }
+    }
+    if placements.len() > FINGERS.len() {
+        // wrap the thumb over the low E
+        if let Some(low_e) = placements
+            .iter_mut()
+            .find(|p| p.is_single() && p.strings[0] == 0)
+        {
+            low_e.thumb = true;
+        }
+    }
+    if placements.iter().filter(|p| !p.thumb).count() > FINGERS.len() {
+        return None;
+    }
+
+    placements.sort_by_key(|p| (p.fret, p.strings[0]));
+
+    let mut fingers: Vec<&'static str> = frets
+        .iter()
+        .map(|fret| if fret.number() == Some(0) { "0" } else { "x" })
+        .collect();
+    let mut next_finger = 0;
+    for placement in placements.iter() {
+        let finger = if placement.thumb {
+            "T"
+        } else {
+            let finger = FINGERS[next_finger];
+            next_finger += 1;
+            finger
+        };
+        for string in placement.strings.iter() {
+            fingers[*string] = finger;
+        }
+    }
+
+    let barres = placements
+        .iter()
+        .filter(|p| p.strings.len() > 1)
+        .map(|p| p.fret)
+        .collect();
+
+    Some(Fingering { fingers, barres })
+}
+
+fn is_finger(finger: &str) -> bool {
+    FINGERS.contains(&finger)
+}
+
+/// Frets which should be barred: the same finger on one fret across several
+/// strings or, when the fingering isn't known, the lowest fret if it can be
+/// barred without covering a lower note.
+pub fn detect_barres(frets: &[Fret], fingers: &[&str]) -> Vec<i32> {
+    let mut barres: Vec<i32> = vec![];
+    for (string, fret) in frets.iter().enumerate() {
+        let finger = fingers.get(string).copied().unwrap_or("x");
+        let Some(fret) = fret.fretted() else {
+            continue;
+        };
+        if !is_finger(finger) || barres.contains(&fret) {
+            continue;
+        }
+        let shared = frets
+            .iter()
+            .zip(fingers.iter())
+            .filter(|(other_fret, other_finger)| {
+                other_fret.fretted() == Some(fret) && **other_finger == finger
+            })
+            .count();
+        if shared > 1 {
+            barres.push(fret);
+        }
+    }
+    if !barres.is_empty() {
+        barres.sort();
+        return barres;
+    }
+
+    let Some(lowest) = frets.iter().filter_map(|fret| fret.fretted()).min() else {
+        return barres;
+    };
+    let strings = find_all(frets, &lowest);
+    if strings.len() < 2 {
+        return barres;
+    }
+    let first = strings[0];
+    let last = strings[strings.len() - 1];
+    let covers_lower = frets[first..=last]
+        .iter()
+        .any(|fret| fret.number().is_none_or(|fret| fret < lowest));
+    let fingered = strings
+        .iter()
+        .any(|string| is_finger(fingers.get(*string).copied().unwrap_or("x")));
+    if !covers_lower && !fingered {
+        barres.push(lowest);
+    }
+    barres
+}
+
+/// Fingers and barres a chord is drawn with: its own, or suggested ones
+/// where it leaves them out
+pub(crate) fn settle_fingering<'a>(chord: &Chord<'a>) -> (Vec<&'a str>, Option<Vec<i32>>) {
+    let fingers = match suggest_fingering(&chord.frets) {
+        Some(fingering) if chord.fingers.is_empty() => fingering.fingers,
+        _ => chord.fingers.clone(),
+    };
+    let barres = match (&chord.barres, chord.manual_barres) {
+        (None, false) => Some(detect_barres(&chord.frets, &fingers)),
+        (barres, _) => barres.clone(),
+    };
+    (fingers, barres)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fingering::{detect_barres, suggest_fingering, Fingering},
+        types::frets_from_numbers,
+    };
+
+    #[test]
+    fn should_finger_open_chords() {
+        // C
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, 3, 2, 0, 1, 0])),
+            Some(Fingering {
+                fingers: vec!["x", "3", "2", "0", "1", "0"],
+                barres: vec![],
+            })
+        );
+        // G
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[3, 2, 0, 0, 0, 3]))
+                .unwrap()
+                .fingers,
+            vec!["2", "1", "0", "0", "0", "3"]
+        );
+        // D
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, -1, 0, 2, 3, 2]))
+                .unwrap()
+                .fingers,
+            vec!["x", "x", "0", "1", "3", "2"]
+        );
+        // Hendrix
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, 7, 6, 7, 8, -1]))
+                .unwrap()
+                .fingers,
+            vec!["x", "2", "1", "3", "4", "x"]
+        );
+    }
+
+    #[test]
+    fn should_barre_lowest_fret() {
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, 3, 5, 5, 4, 3])),
+            Some(Fingering {
+                fingers: vec!["x", "1", "3", "4", "2", "1"],
+                barres: vec![3],
+            })
+        );
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[1, 3, 3, 2, 1, 1])),
+            Some(Fingering {
+                fingers: vec!["1", "3", "4", "2", "1", "1"],
+                barres: vec![1],
+            })
+        );
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[10, 12, 10, 11, 10, 10])),
+            Some(Fingering {
+                fingers: vec!["1", "3", "1", "2", "1", "1"],
+                barres: vec![10],
+            })
+        );
+    }
+
+    #[test]
+    fn should_use_partial_barre() {
+        // E9 - lowest note isn't barred
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, 7, 6, 7, 7, 7])),
+            Some(Fingering {
+                fingers: vec!["x", "2", "1", "3", "3", "3"],
+                barres: vec![7],
+            })
+        );
+    }
+
+    #[test]
+    fn should_use_thumb() {
+        // five notes and no barre possible over the open B
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[2, 4, 2, 3, 0, 2]))
+                .unwrap()
+                .fingers,
+            vec!["T", "4", "1", "3", "0", "2"]
+        );
+    }
+
+    #[test]
+    fn should_not_finger_unplayable_shapes() {
+        // five notes on different frets, with no barre or thumb to help
+        assert_eq!(
+            suggest_fingering(&frets_from_numbers(&[-1, 2, 4, 3, 5, 6])),
+            None
+        );
+    }
+
+    #[test]
+    fn should_detect_barres_from_fingers() {
+        // F
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+                &["1", "3", "4", "2", "1", "1"]
+            ),
+            vec![1]
+        );
+        // D7
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[10, 12, 10, 11, 10, 10]),
+                &["1", "3", "1", "2", "1", "1"]
+            ),
+            vec![10]
+        );
+        // A with one finger
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+                &["x", "0", "1", "1", "1", "0"]
+            ),
+            vec![2]
+        );
+        // separate fingers on the same fret aren't barred
+        let empty: Vec<i32> = vec![];
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+                &["x", "0", "2", "1", "3", "0"]
+            ),
+            empty
+        );
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
+                &["x", "2", "1", "3", "4", "x"]
+            ),
+            empty
+        );
+    }
+
+    #[test]
+    fn should_detect_e9_and_b9_barres() {
+        // barre on the top three strings, not the lowest fret
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[-1, 7, 6, 7, 7, 7]),
+                &["x", "2", "1", "3", "3", "3"]
+            ),
+            vec![7]
+        );
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[-1, 9, 8, 9, 9, 9]),
+                &["x", "2", "1", "3", "3", "3"]
+            ),
+            vec![9]
+        );
+    }
+
+    #[test]
+    fn should_detect_barres_without_fingers() {
+        assert_eq!(
+            detect_barres(&frets_from_numbers(&[-1, 3, 5, 5, 4, 3]), &[]),
+            vec![3]
+        );
+        assert_eq!(
+            detect_barres(
+                &frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+                &["x", "x", "x", "x", "x", "x"]
+            ),
+            vec![1]
+        );
+
+        // lowest note is on its own
+        let empty: Vec<i32> = vec![];
+        assert_eq!(
+            detect_barres(&frets_from_numbers(&[-1, 7, 6, 7, 7, 7]), &[]),
+            empty
+        );
+        // can't barre over an open string
+        assert_eq!(
+            detect_barres(&frets_from_numbers(&[-1, 2, 3, 0, 2, 2]), &[]),
+            empty
+        );
+    }
+}
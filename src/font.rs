@@ -0,0 +1,358 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::path::Path;
+
+use ttf_parser::{Face, FaceParsingError, OutlineBuilder};
+
+/// TrueType or OpenType font used to draw text as paths
+pub struct Font {
+    data: Vec<u8>,
+}
+
+impl Font {
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, FaceParsingError> {
+        Face::parse(&data, 0)?;
+        Ok(Font { data })
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        Ok(Font::from_bytes(std::fs::read(path)?)?)
+    }
+
+    fn face(&self) -> Face<'_> {
+        Face::parse(&self.data, 0).expect("font checked when loaded")
+    }
+}
+
+// two decimal places is plenty at this size
+fn format_number(number: f32) -> String {
+    let number = format!("{:.2}", number);
+    let number = number.trim_end_matches('0').trim_end_matches('.');
+    match number {
+        "-0" => String::from("0"),
+        _ => number.to_string(),
+    }
+}
+
+// glyph outline in font units, moved and scaled onto the diagram
+struct PathBuilder {
+    d: String,
+    x: f32,
+    y: f32,
+    scale: f32,
+}
+
+impl PathBuilder {
+    fn point(&mut self, x: f32, y: f32) {
+        let _ = write!(
+            self.d,
+            "{} {} ",
+            format_number(self.x + x * self.scale),
+            format_number(self.y - y * self.scale)
+        );
+    }
+}
+
+impl OutlineBuilder for PathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.d += "M";
+        self.point(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.d += "L";
+        self.point(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.d += "Q";
+        self.point(x1, y1);
+        self.point(x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.d += "C";
+        self.point(x1, y1);
+        self.point(x2, y2);
+        self.point(x, y);
+    }
+
+    fn close(&mut self) {
+        self.d += "Z";
+    }
+}
+
+fn get_attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let before = rest[..index].chars().last();
+        let after = &rest[index + name.len()..];
+        rest = after;
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(value) = after.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        let value = &value[1..];
+        return value.find(quote).map(|end| value[..end].to_string());
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&")
+}
+
+// piece of text with its own size and colour, from a <text> or <tspan>
+struct Run {
+    text: String,
+    size: f32,
+    fill: Option<String>,
+}
+
+fn get_size(tag: &str) -> Option<f32> {
+    get_attribute(tag, "font-size").and_then(|size| size.trim_end_matches("px").parse().ok())
+}
+
+fn get_runs(tag: &str, content: &str) -> Vec<Run> {
+    let size = get_size(tag).unwrap_or(16.0);
+    let fill = get_attribute(tag, "fill");
+
+    let mut runs = vec![];
+    let mut rest = content;
+    while !rest.is_empty() {
+        let (text, tspan) = match rest.find("<tspan") {
+            Some(start) => (&rest[..start], Some(&rest[start..])),
+            None => (rest, None),
+        };
+        if !text.is_empty() {
+            runs.push(Run {
+                text: unescape(text),
+                size,
+                fill: fill.clone(),
+            });
+        }
+        let Some(tspan) = tspan else {
+            break;
+        };
+        let open_end = tspan.find('>').map(|end| end + 1).unwrap_or(tspan.len());
+        let close = tspan.find("</tspan>").unwrap_or(tspan.len());
+        let open = &tspan[..open_end];
+        runs.push(Run {
+            text: unescape(&tspan[open_end..close.max(open_end)]),
+            size: get_size(open).unwrap_or(size),
+            fill: get_attribute(open, "fill").or(fill.clone()),
+        });
+        rest = tspan.get(close + "</tspan>".len()..).unwrap_or_default();
+    }
+
+    // whitespace collapses as it would in the browser
+    let mut collapsed: Vec<Run> = vec![];
+    let mut last_space = true;
+    for run in runs {
+        let mut text = String::new();
+        for c in run.text.chars() {
+            let space = c.is_whitespace();
+            if !(space && last_space) {
+                text.push(if space { ' ' } else { c });
+            }
+            last_space = space;
+        }
+        collapsed.push(Run { text, ..run });
+    }
+    if let Some(last) = collapsed.last_mut() {
+        last.text = last.text.trim_end().to_string();
+    }
+    collapsed.retain(|run| !run.text.is_empty());
+    collapsed
+}
+
+fn advance(face: &Face, run: &Run) -> f32 {
+    let scale = run.size / face.units_per_em() as f32;
+    run.text
+        .chars()
+        .map(|c| {
+            let glyph = face.glyph_index(c).unwrap_or_default();
+            face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * scale
+        })
+        .sum()
+}
+
+// distance from the baseline to the middle of lower case letters
+fn half_x_height(face: &Face, size: f32) -> f32 {
+    let x_height = face
+        .x_height()
+        .map(|height| height as f32)
+        .unwrap_or_else(|| {
+            face.glyph_index('x')
+                .and_then(|glyph| face.glyph_bounding_box(glyph))
+                .map(|bbox| bbox.y_max as f32)
+                .unwrap_or(face.ascender() as f32 / 2.0)
+        });
+    x_height * size / face.units_per_em() as f32 / 2.0
+}
+
+fn outline_element(face: &Face, tag: &str, content: &str) -> String {
+    let number = |name: &str| {
+        get_attribute(tag, name)
+            .and_then(|value| value.trim_end_matches("px").parse::<f32>().ok())
+            .unwrap_or(0.0)
+    };
+    let runs = get_runs(tag, content);
+    let width: f32 = runs.iter().map(|run| advance(face, run)).sum();
+    let size = get_size(tag).unwrap_or(16.0);
+
+    let mut x = match get_attribute(tag, "text-anchor").as_deref() {
+        Some("middle") => number("x") - width / 2.0,
+        Some("end") => number("x") - width,
+        _ => number("x"),
+    };
+    let y = match get_attribute(tag, "dominant-baseline").as_deref() {
+        Some("middle") | Some("central") => number("y") + half_x_height(face, size),
+        _ => number("y"),
+    };
+    let class = get_attribute(tag, "class")
+        .map(|class| format!(" class=\"{}\"", class))
+        .unwrap_or_default();
+
+    let mut paths = String::new();
+    for run in runs {
+        let mut builder = PathBuilder {
+            d: String::new(),
+            x,
+            y,
+            scale: run.size / face.units_per_em() as f32,
+        };
+        for c in run.text.chars() {
+            let glyph = face.glyph_index(c).unwrap_or_default();
+            face.outline_glyph(glyph, &mut builder);
+            builder.x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32 * builder.scale;
+        }
+        x = builder.x;
+
+        if !builder.d.is_empty() {
+            let fill = run
+                .fill
+                .map(|fill| format!(" fill=\"{}\"", fill))
+                .unwrap_or_default();
+            paths += &format!("<path{}{} d=\"{}\" />", class, fill, builder.d.trim_end());
+        }
+    }
+    paths
+}
+
+/// Replaces each `<text>` in the SVG with glyph outlines from the font, so it
+/// looks the same whichever fonts are installed. Understands `x`, `y`,
+/// `font-size`, `fill`, `text-anchor`, `dominant-baseline` and `<tspan>`s
+/// which change the size or colour.
+pub fn outline_text(svg: &str, font: &Font) -> String {
+    let face = font.face();
+    let mut output = String::with_capacity(svg.len());
+    let mut rest = svg;
+
+    while let Some(start) = rest.find("<text") {
+        let tag_end = rest[start..].find('>').map(|end| start + end + 1);
+        let close = rest[start..].find("</text>").map(|end| start + end);
+        let (Some(tag_end), Some(close)) = (tag_end, close) else {
+            break;
+        };
+        // <textPath> etc
+        if !rest[start + "<text".len()..].starts_with(|c: char| c.is_whitespace() || c == '>') {
+            output += &rest[..tag_end];
+            rest = &rest[tag_end..];
+            continue;
+        }
+
+        output += &rest[..start];
+        output += &outline_element(&face, &rest[start..tag_end], &rest[tag_end..close]);
+        rest = &rest[close + "</text>".len()..];
+    }
+    output += rest;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::font::{format_number, get_attribute, get_runs, outline_text, Font};
+
+    fn get_font() -> Font {
+        Font::from_file("fixtures/fonts/DejaVuSans-subset.ttf").expect("couldn't open font")
+    }
+
+    #[test]
+    fn should_format_numbers() {
+        assert_eq!(format_number(1.0), "1");
+        assert_eq!(format_number(1.5), "1.5");
+        assert_eq!(format_number(1.256), "1.26");
+        assert_eq!(format_number(-0.001), "0");
+    }
+
+    #[test]
+    fn should_get_attributes() {
+        let tag = "<text x=\"150px\" y=\"18\" class=\"text\"\n  font-size='24' fill=\"#fff\">";
+        assert_eq!(get_attribute(tag, "x"), Some(String::from("150px")));
+        assert_eq!(get_attribute(tag, "y"), Some(String::from("18")));
+        assert_eq!(get_attribute(tag, "size"), None);
+        assert_eq!(get_attribute(tag, "font-size"), Some(String::from("24")));
+        assert_eq!(get_attribute(tag, "text-anchor"), None);
+    }
+
+    #[test]
+    fn should_split_tspans() {
+        let runs = get_runs(
+            "<text font-size=\"24\" fill=\"#fff\">",
+            "E&amp;<tspan font-size=\"18\" fill=\"#000\">7</tspan>\n  ",
+        );
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].text, "E&");
+        assert_eq!(runs[0].size, 24.0);
+        assert_eq!(runs[1].text, "7");
+        assert_eq!(runs[1].size, 18.0);
+        assert_eq!(runs[1].fill, Some(String::from("#000")));
+    }
+
+    #[test]
+    fn should_reject_bad_fonts() {
+        assert!(Font::from_bytes(vec![0, 1, 2, 3]).is_err());
+        assert!(Font::from_file("fixtures/fonts/missing.ttf").is_err());
+    }
+
+    #[test]
+    fn should_outline_text() {
+        let font = get_font();
+        let svg = "<svg><text x=\"10\" y=\"20\" font-size=\"10\" fill=\"#fff\">-</text></svg>";
+        // hyphen is a rectangle
+        assert_eq!(
+            outline_text(svg, &font),
+            "<svg><path fill=\"#fff\" d=\"M10.49 16.86 L13.12 16.86 L13.12 17.66 L10.49 17.66 L10.49 16.86 Z\" /></svg>"
+        );
+
+        // nothing drawn for spaces
+        let svg = "<svg><text x=\"10\" y=\"20\"> </text></svg>";
+        assert_eq!(outline_text(svg, &font), "<svg></svg>");
+    }
+
+    #[test]
+    fn should_anchor_text() {
+        let font = get_font();
+        let start = outline_text("<text x=\"100\" y=\"20\">-</text>", &font);
+        let middle = outline_text(
+            "<text x=\"100\" y=\"20\" text-anchor=\"middle\">-</text>",
+            &font,
+        );
+        let end = outline_text(
+            "<text x=\"100\" y=\"20\" text-anchor=\"end\">-</text>",
+            &font,
+        );
+        // hyphen is 5.77 wide
+        assert!(start.starts_with("<path d=\"M100.78 14.98"));
+        assert!(middle.starts_with("<path d=\"M97.89 14.98"));
+        assert!(end.starts_with("<path d=\"M95.01 14.98"));
+    }
+}
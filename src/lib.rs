@@ -1,95 +1,298 @@
+use audio::generate_wav;
+use describe::{chord_label, describe_settled};
+use fingering::settle_fingering;
+use font::outline_text;
+use metadata::chord_metadata;
+use midi::generate_midi;
+use minify::minify;
+use neck::render_neck_template;
+use row::render_row_template;
+use scale::render_scale_template;
+use spelling::{chord_note_name, localise_title, tuning_names};
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
+use styles::use_css_variables;
 use svg::{
-    svg_draw_barres, svg_draw_bg, svg_draw_finger, svg_draw_min_fret, svg_draw_note, svg_draw_title,
+    svg_draw_barres, svg_draw_bg, svg_draw_difficulty, svg_draw_dot_label, svg_draw_finger,
+    svg_draw_footer, svg_draw_min_fret, svg_draw_note, svg_draw_optional_note, svg_draw_spelling,
+    svg_draw_string_marker, svg_draw_subtitle, svg_draw_title, svg_draw_tuning,
 };
 use tera::{Context as TeraContext, Tera};
-use types::{Chord, GuitarString, Hand};
-use utils::{get_filename, get_palette};
+use theory::{string_note, title_root};
+use types::{Chord, Footer, Fret, GuitarString, Mode, Neck, Scale};
+use utils::{
+    escape_xml, get_chord_height, get_filename, get_fret_lines, get_height, get_note_coords,
+    get_palette, get_string_lines, Dot, Transform, SPELLING_HEIGHT, TUNING_HEIGHT, WIDTH,
+};
 
+mod analysis;
+mod audio;
+mod caged;
+mod describe;
+mod fingering;
+mod font;
+mod metadata;
+mod midi;
+mod minify;
+mod neck;
+mod numerals;
+mod progression;
+mod row;
+mod scale;
+mod spelling;
+mod styles;
 mod svg;
+mod tab;
+mod theory;
 pub mod types;
 mod utils;
+mod voicing;
+
+pub use analysis::{analyse, sort_by_difficulty, Difficulty};
+pub use caged::{caged, Caged, CagedShape};
+pub use describe::describe;
+pub use fingering::{detect_barres, suggest_fingering, Fingering};
+pub use font::Font;
+pub use metadata::{parse_svg_metadata, read_svg_metadata, ChordDefinition};
+pub use numerals::{resolve_numeral, resolve_progression};
+pub use progression::{voice_progression, VoiceLeading};
+pub use scale::{chord_tones, scale_notes, ScaleNote};
+pub use spelling::{
+    chord_spelling, localise, localise_symbol, name_chord, name_in_chord, name_in_key, note_name,
+    spell, to_english, transpose,
+};
+pub use tab::tab_staff;
+pub use theory::{parse_chord, parse_formula, pitch_class, scale_intervals, split_note};
+pub use voicing::{inversions, Inversion, Voicing};
+
+const TEMPLATE: &str = include_str!("../templates/chord.svg");
+
+/// SVG markup for the chord, using the built-in template
+pub fn generate_svg(
+    chord_settings: Chord,
+) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    Renderer::default().generate_svg(chord_settings)
+}
+
+// `row_index` keeps ids unique when the same chord is drawn twice in a row
+fn render_template(
+    template: &str,
+    mut chord_settings: Chord,
+    row_index: Option<usize>,
+) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    let (fingers, barres) = settle_fingering(&chord_settings);
+    chord_settings.fingers = fingers;
+    chord_settings.barres = barres;
+
+    // Taken after filling in fingers and barres so both match what's drawn.
+    // ids need to be unique when several diagrams are inlined in one page.
+    let id = match row_index {
+        Some(index) => format!("{}-{}", get_filename(&chord_settings), index),
+        None => get_filename(&chord_settings).to_string(),
+    };
+    let metadata = chord_metadata(&chord_settings)?;
 
-fn generate_svg(chord_settings: Chord) -> std::result::Result<String, Box<dyn std::error::Error>> {
     let string_space = 40;
     let margin = 30;
+    let height = get_chord_height(&chord_settings);
 
     let palette = get_palette(chord_settings.mode);
+    // under the fingers, where the footer would be without them
+    let mut panel_top = get_height(&Footer::None);
+    let tuning = if chord_settings.show_tuning {
+        let tuning = svg_draw_tuning(
+            &tuning_names(&chord_settings),
+            &Transform::new(chord_settings.hand),
+            &string_space,
+            &panel_top,
+            &palette,
+        );
+        panel_top += TUNING_HEIGHT;
+        tuning
+    } else {
+        String::new()
+    };
+    let spelling = if chord_settings.show_spelling {
+        let spelling = svg_draw_spelling(
+            &chord_spelling(&chord_settings),
+            &Transform::new(chord_settings.hand),
+            &string_space,
+            &panel_top,
+            &palette,
+        );
+        panel_top += SPELLING_HEIGHT;
+        spelling
+    } else {
+        String::new()
+    };
+    let subtitle = match chord_settings.subtitle {
+        Some(subtitle) => svg_draw_subtitle(subtitle, &(panel_top + 10), &palette),
+        None => String::new(),
+    };
 
-    // var for switching between handedness
-    let total_strings = 5;
+    let transform = Transform::new(chord_settings.hand);
 
     let mut fingers = "".to_string();
-    for (i, finger) in chord_settings.fingers.iter().enumerate() {
-        let string: GuitarString = if chord_settings.hand == Hand::Right {
-            i.into()
-        } else {
-            (total_strings - i).into()
-        };
-        fingers += &svg_draw_finger(finger, string, &string_space, &palette);
+    let mut markers = "".to_string();
+    for (i, fret) in chord_settings.frets.iter().enumerate() {
+        match (fret.fretted(), chord_settings.fingers.get(i)) {
+            (Some(_), Some(finger)) => {
+                fingers += &svg_draw_finger(finger, transform.string(i), &string_space, &palette)
+            }
+            (Some(_), None) => (),
+            (None, _) => {
+                markers +=
+                    &svg_draw_string_marker(fret, transform.string(i), &string_space, &palette)
+            }
+        }
     }
 
-    let lowest_fret: &i32 = chord_settings
+    let lowest_fret = &chord_settings
         .frets
         .iter()
-        .filter(|fret| **fret > 0)
+        .filter_map(|fret| fret.fretted())
         .min()
-        .unwrap_or(&0);
+        .unwrap_or(0);
 
-    let show_nut = (chord_settings.frets.contains(&0) && lowest_fret < &3)
-        || chord_settings.frets.contains(&1);
+    let has_open = chord_settings
+        .frets
+        .iter()
+        .any(|fret| fret.number() == Some(0));
+    let show_nut = (has_open && lowest_fret < &3) || lowest_fret == &1;
     let nut_width = if show_nut { 9 } else { 2 };
     let nut_shape = if show_nut { "round" } else { "butt" };
 
+    let root = chord_settings.title.and_then(|title| title_root(title));
     let mut notes = "".to_string();
-    for (i, note) in chord_settings.frets.iter().enumerate() {
-        if note != &0 {
-            let string: GuitarString = if chord_settings.hand == Hand::Right {
-                i.into()
-            } else {
-                (total_strings - i).into()
-            };
-            notes += &svg_draw_note(note, string, &string_space, lowest_fret, &palette);
+    let mut dots: Vec<Dot> = vec![];
+    let mut labels = "".to_string(); // drawn over the dots
+    for (i, fret) in chord_settings.frets.iter().enumerate() {
+        let pitch = chord_settings
+            .tuning
+            .0
+            .get(i)
+            .and_then(|open| string_note(*open, fret))
+            .map(|note| note % 12);
+        let is_root = pitch.is_some_and(|pitch| Some(pitch) == root);
+        if let Some(note) = fret.fretted() {
+            let (x, y) = get_note_coords(&note, transform.string(i), &string_space, lowest_fret);
+            let name = pitch
+                .map(|pitch| chord_note_name(&chord_settings, pitch))
+                .unwrap_or_default();
+            let optional = matches!(fret, Fret::Optional(_));
+            if chord_settings.note_names {
+                labels += &svg_draw_dot_label(&name, &x, &y, optional, &palette);
+            }
+            dots.push(Dot {
+                x,
+                y,
+                string: i,
+                fret: note,
+                finger: chord_settings.fingers.get(i).unwrap_or(&"").to_string(),
+                optional,
+                note: name,
+            });
+        }
+        match fret {
+            Fret::Fretted(note) => {
+                notes += &svg_draw_note(
+                    note,
+                    transform.string(i),
+                    &string_space,
+                    lowest_fret,
+                    is_root,
+                    &palette,
+                )
+            }
+            Fret::Optional(note) => {
+                notes += &svg_draw_optional_note(
+                    note,
+                    transform.string(i),
+                    &string_space,
+                    lowest_fret,
+                    is_root,
+                    &palette,
+                )
+            }
+            _ => (),
         }
     }
 
+    notes += &labels;
+
     let mut min_fret_marker = "".to_string();
     if *lowest_fret > 2 || *lowest_fret > 1 && !show_nut {
         min_fret_marker = svg_draw_min_fret(lowest_fret, &string_space, &palette);
     }
 
+    let difficulty = if chord_settings.show_difficulty {
+        svg_draw_difficulty(analyse(&chord_settings).grade, &palette)
+    } else {
+        String::from("")
+    };
+
+    // notes are named in English until here, where they're written in the
+    // chord's naming system
+    let (title, suffix) = localise_title(&chord_settings);
+    let chord_settings = Chord {
+        title: title.as_ref().or(chord_settings.title),
+        suffix: suffix.as_ref(),
+        ..chord_settings
+    };
+
     let chord_title = svg_draw_title(&chord_settings, &palette);
-    // if barre
-    // for each barre
-    let barres = match chord_settings.barres {
-        Some(barres) => svg_draw_barres(
-            &barres[0],
+    let mut barres = "".to_string();
+    for barre in chord_settings.barres.iter().flatten() {
+        barres += &svg_draw_barres(
+            barre,
             &chord_settings.frets,
             &string_space,
             lowest_fret,
+            &transform,
             &palette,
-        ),
-        None => String::from(""),
-    };
+        );
+    }
+
+    let label = chord_label(&chord_settings).unwrap_or_else(|| String::from("Chord"));
+    let description = describe_settled(&chord_settings);
 
     let mut context = TeraContext::new();
+    context.insert("titleId", &format!("title-{}", id));
+    context.insert("descId", &format!("desc-{}", id));
+    context.insert("label", &escape_xml(&format!("{} chord diagram", label)));
+    context.insert("description", &escape_xml(&description));
+    context.insert("metadata", &metadata);
     context.insert("name", &chord_title);
     context.insert("padding", &margin);
     context.insert("nutWidth", &nut_width);
     context.insert("nutShape", &nut_shape);
     context.insert("fingers", &fingers);
+    context.insert("markers", &markers);
     context.insert("notes", &notes);
     context.insert("minFret", &min_fret_marker);
+    context.insert("difficulty", &difficulty);
     context.insert("foreground", &palette.fg);
     context.insert(
         "background",
-        &svg_draw_bg(chord_settings.use_background, &palette),
+        &svg_draw_bg(chord_settings.use_background, &WIDTH, &height, &palette),
     );
     context.insert("barres", &barres);
+    context.insert("width", &WIDTH);
+    context.insert("height", &height);
+    context.insert("tuning", &tuning);
+    context.insert("spelling", &spelling);
+    context.insert("subtitle", &subtitle);
+    context.insert(
+        "footer",
+        &svg_draw_footer(&chord_settings.footer, &height, &palette),
+    );
+    context.insert("stringSpace", &string_space);
+    context.insert("strings", &get_string_lines(&string_space));
+    context.insert("frets", &get_fret_lines(&string_space));
+    context.insert("dots", &dots);
 
-    match Tera::one_off(include_str!("../templates/chord.svg"), &context, false) {
+    match Tera::one_off(template, &context, false) {
         Ok(result) => Ok(result),
         Err(e) => {
             println!("{:?}", e);
@@ -98,32 +301,243 @@ fn generate_svg(chord_settings: Chord) -> std::result::Result<String, Box<dyn st
     }
 }
 
+/// Draws chords with the built-in template, or a Tera template of your own.
+/// See the readme for the variables available to templates.
+pub struct Renderer {
+    template: String,
+    font: Option<Font>, // draw text as paths
+    compact: bool,
+    css_variables: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer {
+            template: TEMPLATE.to_string(),
+            font: None,
+            compact: false,
+            css_variables: false,
+        }
+    }
+}
+
+impl Renderer {
+    pub fn with_template<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Renderer {
+            template: std::fs::read_to_string(path)?,
+            ..Default::default()
+        })
+    }
+
+    /// Converts text to outlines from the font, so diagrams look the same
+    /// on every machine
+    pub fn with_font(self, font: Font) -> Self {
+        Renderer {
+            font: Some(font),
+            ..self
+        }
+    }
+
+    /// Smaller output for pages with lots of diagrams, which draws the same
+    pub fn compact(self) -> Self {
+        Renderer {
+            compact: true,
+            ..self
+        }
+    }
+
+    /// Colours come from CSS custom properties rather than attributes, so a
+    /// page can restyle diagrams, eg for dark mode. See the readme.
+    pub fn css_variables(self) -> Self {
+        Renderer {
+            css_variables: true,
+            ..self
+        }
+    }
+
+    fn finish(&self, mut svg: String, mode: Mode) -> String {
+        // a single diagram can only switch palettes with a stylesheet
+        if self.css_variables || mode == Mode::Auto {
+            svg = use_css_variables(&svg, mode);
+        }
+        if let Some(font) = &self.font {
+            svg = outline_text(&svg, font);
+        }
+        if self.compact {
+            svg = minify(&svg, &get_palette(mode));
+        }
+        svg
+    }
+
+    pub fn generate_svg(
+        &self,
+        chord_settings: Chord,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mode = chord_settings.mode;
+        let svg = render_template(&self.template, chord_settings, None)?;
+        Ok(self.finish(svg, mode))
+    }
+
+    /// Scale or arpeggio diagram. These always use the built-in scale template.
+    pub fn generate_scale_svg(&self, scale: &Scale) -> Result<String, Box<dyn std::error::Error>> {
+        let svg = render_scale_template(scale)?;
+        Ok(self.finish(svg, scale.mode))
+    }
+
+    pub fn render_scale_svg(
+        &self,
+        scale: &Scale,
+        output_dir: &str,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let hashed_title = get_filename(scale);
+
+        let path = Path::new(output_dir).join(format!("{}.svg", hashed_title));
+        let mut output = File::create(path)?;
+        write!(output, "{}", self.generate_scale_svg(scale)?)?;
+        Ok(hashed_title)
+    }
+
+    /// Notes across the whole neck, drawn sideways. These always use the
+    /// built-in neck template.
+    pub fn generate_neck_svg(&self, neck: &Neck) -> Result<String, Box<dyn std::error::Error>> {
+        let svg = render_neck_template(neck)?;
+        Ok(self.finish(svg, neck.scale.mode))
+    }
+
+    pub fn render_neck_svg(
+        &self,
+        neck: &Neck,
+        output_dir: &str,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let hashed_title = get_filename(neck);
+
+        let path = Path::new(output_dir).join(format!("{}.svg", hashed_title));
+        let mut output = File::create(path)?;
+        write!(output, "{}", self.generate_neck_svg(neck)?)?;
+        Ok(hashed_title)
+    }
+
+    /// Diagrams side by side in one SVG, eg the chords of a progression
+    pub fn generate_row_svg(
+        &self,
+        chords: Vec<Chord>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let mode = chords.first().map(|chord| chord.mode).unwrap_or_default();
+        let svg = render_row_template(&self.template, chords)?;
+        Ok(self.finish(svg, mode))
+    }
+
+    pub fn render_row_svg(
+        &self,
+        chords: Vec<Chord>,
+        output_dir: &str,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let hashed_title = get_filename(&chords);
+
+        let path = Path::new(output_dir).join(format!("{}.svg", hashed_title));
+        let mut output = File::create(path)?;
+        write!(output, "{}", self.generate_row_svg(chords)?)?;
+        Ok(hashed_title)
+    }
+
+    pub fn render_svg(
+        &self,
+        chord_settings: Chord,
+        output_dir: &str,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let hashed_title = get_filename(&chord_settings);
+
+        match self.generate_svg(chord_settings) {
+            Ok(result) => {
+                let path = Path::new(output_dir).join(format!("{}.svg", hashed_title));
+                let mut output = File::create(path)?;
+                write!(output, "{}", result)?;
+                Ok(hashed_title)
+            }
+
+            Err(e) => {
+                println!("Failed to create SVG: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
 pub fn render_svg(
     chord_settings: Chord,
     output_dir: &str,
 ) -> Result<u64, Box<dyn std::error::Error>> {
-    let hashed_title = get_filename(&chord_settings);
-
-    match generate_svg(chord_settings) {
-        Ok(result) => {
-            let path = Path::new(output_dir).join(format!("{}.svg", hashed_title));
-            let mut output = File::create(path)?;
-            write!(output, "{}", result)?;
-            Ok(hashed_title)
-        }
+    Renderer::default().render_svg(chord_settings, output_dir)
+}
 
-        Err(e) => {
-            println!("Failed to create SVG: {:?}", e);
-            Err(e)
-        }
-    }
+/// SVG markup for diagrams side by side
+pub fn generate_row_svg(chords: Vec<Chord>) -> Result<String, Box<dyn std::error::Error>> {
+    Renderer::default().generate_row_svg(chords)
+}
+
+pub fn render_row_svg(
+    chords: Vec<Chord>,
+    output_dir: &str,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    Renderer::default().render_row_svg(chords, output_dir)
+}
+
+/// SVG markup for a scale or arpeggio
+pub fn generate_scale_svg(scale: &Scale) -> Result<String, Box<dyn std::error::Error>> {
+    Renderer::default().generate_scale_svg(scale)
+}
+
+pub fn render_scale_svg(
+    scale: &Scale,
+    output_dir: &str,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    Renderer::default().render_scale_svg(scale, output_dir)
+}
+
+/// SVG markup for notes across the whole neck
+pub fn generate_neck_svg(neck: &Neck) -> Result<String, Box<dyn std::error::Error>> {
+    Renderer::default().generate_neck_svg(neck)
+}
+
+pub fn render_neck_svg(neck: &Neck, output_dir: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    Renderer::default().render_neck_svg(neck, output_dir)
+}
+
+pub fn render_midi(
+    chord_settings: &Chord,
+    output_dir: &str,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let hashed_title = get_filename(chord_settings);
+
+    let path = Path::new(output_dir).join(format!("{}.mid", hashed_title));
+    let mut output = File::create(path)?;
+    output.write_all(&generate_midi(chord_settings))?;
+    Ok(hashed_title)
+}
+
+pub fn render_wav(
+    chord_settings: &Chord,
+    output_dir: &str,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let hashed_title = get_filename(chord_settings);
+
+    let path = Path::new(output_dir).join(format!("{}.wav", hashed_title));
+    let mut output = File::create(path)?;
+    output.write_all(&generate_wav(chord_settings))?;
+    Ok(hashed_title)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        generate_svg,
-        types::{Chord, Hand, Mode},
+        caged, chord_tones, generate_neck_svg, generate_row_svg, generate_scale_svg, generate_svg,
+        inversions, resolve_progression,
+        types::{
+            frets_from_numbers, position, Chord, Footer, Fret, Hand, Mode, Neck, NoteNaming, Scale,
+            Tuning,
+        },
+        utils::get_palette,
+        voice_progression, Font, Renderer, VoiceLeading, Voicing,
     };
 
     #[test]
@@ -131,7 +545,7 @@ mod tests {
         let title = String::from("Hendrix");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 7, 6, 7, 8, -1],
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
             fingers: vec!["x", "2", "1", "3", "4", "x"],
             ..Default::default()
         };
@@ -143,7 +557,7 @@ mod tests {
         let title = String::from("E");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![0, 2, 2, 1, 0, 0],
+            frets: frets_from_numbers(&[0, 2, 2, 1, 0, 0]),
             fingers: vec!["0", "2", "3", "1", "0", "0"],
             ..Default::default()
         };
@@ -156,7 +570,7 @@ mod tests {
         let suffix = String::from("°7");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 3, 4, 2, 3, -1],
+            frets: frets_from_numbers(&[-1, 3, 4, 2, 3, -1]),
             suffix: Some(&suffix),
             fingers: vec!["x", "2", "3", "1", "4", "x"],
             ..Default::default()
@@ -169,7 +583,7 @@ mod tests {
         let title = String::from("E9");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 7, 6, 7, 7, 7],
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 7, 7]),
             fingers: vec!["x", "2", "1", "3", "3", "3"],
             ..Default::default()
         };
@@ -181,7 +595,7 @@ mod tests {
         let title = String::from("D7");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![10, 12, 10, 11, 10, 10],
+            frets: frets_from_numbers(&[10, 12, 10, 11, 10, 10]),
             fingers: vec!["1", "3", "1", "2", "1", "1"],
             ..Default::default()
         };
@@ -193,7 +607,7 @@ mod tests {
         let title = String::from("Bond");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![0, 10, 9, 8, 7, -1],
+            frets: frets_from_numbers(&[0, 10, 9, 8, 7, -1]),
             fingers: vec!["0", "4", "3", "2", "1", "x"],
             hand: Hand::Right,
             ..Default::default()
@@ -211,7 +625,7 @@ mod tests {
         let title = String::from("B");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 9, 8, 9, 9, 9],
+            frets: frets_from_numbers(&[-1, 9, 8, 9, 9, 9]),
             fingers: vec!["x", "2", "1", "3", "3", "3"],
             suffix: Some(&suffix),
             barres: Some(vec![9]),
@@ -227,7 +641,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 3, 5, 5, 4, 3],
+            frets: frets_from_numbers(&[-1, 3, 5, 5, 4, 3]),
             fingers: vec!["x", "1", "3", "4", "2", "1"],
             barres: Some(vec![3]),
             ..Default::default()
@@ -238,12 +652,44 @@ mod tests {
         assert_eq!(image.unwrap(), expected);
     }
 
+    #[test]
+    fn should_detect_barres() {
+        // B9 without barres passed in
+        let suffix = String::from("9");
+        let title = String::from("B");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 9, 8, 9, 9, 9]),
+            fingers: vec!["x", "2", "1", "3", "3", "3"],
+            suffix: Some(&suffix),
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/9333158008996547180.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // opted out
+        let title = String::from("E9");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 7, 7]),
+            fingers: vec!["x", "2", "1", "3", "3", "3"],
+            manual_barres: true,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/manual/7096726079496818499.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
     #[test]
     fn should_render_barre_at_nut() {
         let title = String::from("F");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![1, 3, 3, 2, 1, 1],
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
             fingers: vec!["1", "3", "4", "2", "1", "1"],
             barres: Some(vec![1]),
             ..Default::default()
@@ -261,7 +707,7 @@ mod tests {
         let suffix = String::from("m69");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 5, 3, 4, 5, 0],
+            frets: frets_from_numbers(&[-1, 5, 3, 4, 5, 0]),
             fingers: vec!["x", "3", "1", "2", "4", "0"],
             suffix: Some(&suffix),
             ..Default::default()
@@ -281,7 +727,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             ..Default::default()
         };
@@ -294,7 +740,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             hand: Hand::Left,
             ..Default::default()
@@ -308,7 +754,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             use_background: true,
             ..Default::default()
@@ -322,7 +768,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             hand: Hand::Left,
             use_background: true,
@@ -343,7 +789,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             ..Default::default()
         };
@@ -356,7 +802,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             use_background: true,
             ..Default::default()
@@ -370,7 +816,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             mode: Mode::Dark,
             ..Default::default()
@@ -384,7 +830,7 @@ mod tests {
         let chord = Chord {
             title: Some(&title),
             suffix: Some(&suffix),
-            frets: vec![-1, 6, 5, 6, -1, -1],
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
             fingers: vec!["x", "2", "1", "3", "x", "x"],
             use_background: true,
             mode: Mode::Dark,
@@ -394,6 +840,55 @@ mod tests {
         let expected = std::fs::read_to_string("fixtures/1048205031866609166.svg")
             .expect("couldn't open fixture");
         assert_eq!(image.unwrap(), expected);
+
+        // auto bg rh
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+            fingers: vec!["x", "2", "1", "3", "x", "x"],
+            use_background: true,
+            mode: Mode::Auto,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/12406909464654113154.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_difficulty_badge() {
+        let title = String::from("F");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+            fingers: vec!["1", "3", "4", "2", "1", "1"],
+            barres: Some(vec![1]),
+            show_difficulty: true,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/difficulty/10048645889283263481.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_suggest_missing_fingers() {
+        let title = String::from("C");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 3, 5, 5, 4, 3]),
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        // same as passing fingers and barres in
+        let expected = std::fs::read_to_string("fixtures/12230973133991337290.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
     }
 
     #[test]
@@ -401,7 +896,7 @@ mod tests {
         let title = String::from("A");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 0, 2, 2, 2, 0],
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
             fingers: vec!["x", "0", "2", "1", "3", "0"],
             hand: Hand::Left,
             ..Default::default()
@@ -414,7 +909,7 @@ mod tests {
         let title = String::from("Hendrix");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 7, 6, 7, 8, -1],
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
             fingers: vec!["x", "2", "1", "3", "4", "x"],
             hand: Hand::Left,
             ..Default::default()
@@ -424,6 +919,611 @@ mod tests {
             .expect("couldn't open fixture");
         assert_eq!(image.unwrap(), expected);
     }
+
+    #[test]
+    fn should_render_left_handed_barres() {
+        let title = String::from("F");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+            fingers: vec!["1", "3", "4", "2", "1", "1"],
+            barres: Some(vec![1]),
+            hand: Hand::Left,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/left/14453129114705198458.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        let title = String::from("C");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 3, 5, 5, 4, 3]),
+            fingers: vec!["x", "1", "3", "4", "2", "1"],
+            barres: Some(vec![3]),
+            hand: Hand::Left,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/left/11985018220970993407.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // partial barre on the top strings moves to the left
+        let title = String::from("B");
+        let suffix = String::from("9");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 9, 8, 9, 9, 9]),
+            fingers: vec!["x", "2", "1", "3", "3", "3"],
+            barres: Some(vec![9]),
+            hand: Hand::Left,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/left/6937211239301138710.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_string_markers() {
+        // optional bass note and open string
+        let title = String::from("C");
+        let suffix = String::from("/G");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: vec![
+                Fret::Optional(3),
+                Fret::Fretted(3),
+                Fret::Fretted(2),
+                Fret::Open,
+                Fret::Fretted(1),
+                Fret::Optional(0),
+            ],
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/markers/929495571736910104.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // unplayed low E, muted A
+        let title = String::from("D");
+        let chord = Chord {
+            title: Some(&title),
+            frets: vec![
+                Fret::Unplayed,
+                Fret::Muted,
+                Fret::Open,
+                Fret::Fretted(2),
+                Fret::Fretted(3),
+                Fret::Fretted(2),
+            ],
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/markers/11715998214503302289.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_custom_template() {
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            ..Default::default()
+        };
+        let renderer = Renderer::with_template("fixtures/templates/minimal.svg").unwrap();
+        let image = renderer.generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/templates/8506728644459100974.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        assert!(Renderer::with_template("fixtures/templates/missing.svg").is_err());
+    }
+
+    #[test]
+    fn should_render_footer() {
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            use_background: true,
+            footer: Footer::None,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/footer/4907396695348951277.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            footer: Footer::Link {
+                text: String::from("Example Co. songbook"),
+                href: String::from("https://example.com/songbook"),
+            },
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/footer/15765798981159332197.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_outline_text() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+            fingers: vec!["x", "2", "1", "3", "x", "x"],
+            show_difficulty: true,
+            ..Default::default()
+        };
+        let font = Font::from_file("fixtures/fonts/DejaVuSans-subset.ttf").unwrap();
+        let image = Renderer::default().with_font(font).generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/fonts/16809337151046717538.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_compact() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+            fingers: vec!["x", "2", "1", "3", "x", "x"],
+            mode: Mode::Dark,
+            use_background: true,
+            ..Default::default()
+        };
+        let image = Renderer::default().compact().generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/compact/1048205031866609166.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_css_variables() {
+        let title = String::from("F");
+        let chord = Chord {
+            title: Some(&title),
+            frets: vec![
+                Fret::Fretted(1),
+                Fret::Fretted(3),
+                Fret::Fretted(3),
+                Fret::Fretted(2),
+                Fret::Fretted(1),
+                Fret::Optional(1),
+            ],
+            mode: Mode::Dark,
+            use_background: true,
+            show_difficulty: true,
+            ..Default::default()
+        };
+        let image = Renderer::default().css_variables().generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/css/16302161998398472928.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_scales() {
+        // A minor pentatonic, 5th position
+        let title = String::from("A minor pentatonic");
+        let scale = Scale {
+            title: Some(&title),
+            root: 9,
+            intervals: vec![0, 3, 5, 7, 10],
+            frets: position(5),
+            use_background: true,
+            ..Default::default()
+        };
+        let image = generate_scale_svg(&scale);
+        let expected = std::fs::read_to_string("fixtures/scales/14377897748554859550.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // whole neck, with open strings
+        let title = String::from("G major");
+        let scale = Scale {
+            title: Some(&title),
+            root: 7,
+            frets: 0..=12,
+            mode: Mode::Dark,
+            use_background: true,
+            ..Default::default()
+        };
+        let image = generate_scale_svg(&scale);
+        let expected = std::fs::read_to_string("fixtures/scales/5304601726150592844.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // backwards ranges have no neck to draw
+        let scale = Scale {
+            frets: std::ops::RangeInclusive::new(10, 5),
+            ..Default::default()
+        };
+        assert!(generate_scale_svg(&scale).is_err());
+    }
+
+    #[test]
+    fn should_render_caged_shapes() {
+        // G, E and C shapes
+        let title = String::from("G");
+        let shapes = caged(7, false);
+        for (shape, fixture) in [
+            (&shapes[1], "fixtures/caged/12696574937195919308.svg"),
+            (&shapes[3], "fixtures/caged/6650316186079497937.svg"),
+        ] {
+            let chord = Chord {
+                use_background: true,
+                footer: Footer::Text(format!("{} shape", shape.shape)),
+                ..shape.chord(Some(&title), None)
+            };
+            let image = generate_svg(chord);
+            let expected = std::fs::read_to_string(fixture).expect("couldn't open fixture");
+            assert_eq!(image.unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn should_render_inversions() {
+        // Cmaj7 drop 2 on D-G-B-E, with the inversion under the diagram
+        let title = String::from("C");
+        let suffix = String::from("maj7");
+        let found = inversions(
+            0,
+            &[0, 4, 7, 11],
+            Voicing::Drop2,
+            &[2, 3, 4, 5],
+            &Tuning::default(),
+        );
+        let label = found[1].label();
+        let chord = Chord {
+            use_background: true,
+            ..found[1].chord(Some(&title), Some(&suffix), Some(&label))
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/inversions/1770779439001881409.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_note_names() {
+        // A♭ spelled from the chord, so its third is C rather than B♯
+        let title = String::from("A♭");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[4, 6, 6, 5, 4, 4]),
+            use_background: true,
+            note_names: true,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/notes/9995159341048508109.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_german_names() {
+        // B minor, written with H for B in the title and on the dots
+        let title = String::from("B");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 2, 4, 4, 3, 2]),
+            use_background: true,
+            note_names: true,
+            naming: NoteNaming::German,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/naming/5311712172807607503.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_tuning_labels() {
+        // the B string is H in German too
+        let title = String::from("B");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 2, 4, 4, 3, 2]),
+            use_background: true,
+            note_names: true,
+            naming: NoteNaming::German,
+            show_tuning: true,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/naming/9798585441957214684.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_spelling() {
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            use_background: true,
+            show_spelling: true,
+            ..Default::default()
+        };
+        let image = generate_svg(chord);
+        let expected = std::fs::read_to_string("fixtures/spelling/5891791803238516412.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_progression_row() {
+        let limits = VoiceLeading {
+            frets: 5..=10,
+            ..Default::default()
+        };
+        let voicings = voice_progression("Am7 D7 Gmaj7 Cmaj7", &limits).unwrap();
+        let names = [("A", "m7"), ("D", "7"), ("G", "maj7"), ("C", "maj7")]
+            .map(|(title, suffix)| (String::from(title), String::from(suffix)));
+        let chords = voicings
+            .iter()
+            .zip(names.iter())
+            .map(|(voicing, (title, suffix))| Chord {
+                use_background: true,
+                footer: Footer::None,
+                ..voicing.chord(Some(title), Some(suffix), None)
+            })
+            .collect();
+        let image = generate_row_svg(chords);
+        let expected = std::fs::read_to_string("fixtures/rows/4796780528406541714.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_numerals() {
+        let limits = VoiceLeading {
+            frets: 2..=9,
+            ..Default::default()
+        };
+        let symbols = resolve_progression("I vi IV V", "G").unwrap();
+        let voicings = voice_progression(&symbols.join(" "), &limits).unwrap();
+        let names = [
+            ("G", "", "I"),
+            ("E", "m", "vi"),
+            ("C", "", "IV"),
+            ("D", "", "V"),
+        ]
+        .map(|(title, suffix, numeral)| {
+            (
+                String::from(title),
+                String::from(suffix),
+                String::from(numeral),
+            )
+        });
+        let chords = voicings
+            .iter()
+            .zip(names.iter())
+            .map(|(voicing, (title, suffix, numeral))| Chord {
+                use_background: true,
+                footer: Footer::None,
+                ..voicing.chord(
+                    Some(title),
+                    (!suffix.is_empty()).then_some(suffix),
+                    Some(numeral),
+                )
+            })
+            .collect();
+        let image = generate_row_svg(chords);
+        let expected = std::fs::read_to_string("fixtures/rows/968127692056615077.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    #[test]
+    fn should_render_neck() {
+        // chord tones with the shape picked out
+        let title = String::from("C");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 3, 2, 0, 1, 0]),
+            use_background: true,
+            ..Default::default()
+        };
+        let mut scale = chord_tones(&chord);
+        scale.frets = 0..=12;
+        let neck = Neck {
+            scale,
+            shape: Some(chord),
+        };
+        let image = generate_neck_svg(&neck);
+        let expected = std::fs::read_to_string("fixtures/neck/17990467701809109861.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+
+        // scale, left-handed
+        let title = String::from("A minor pentatonic");
+        let neck = Neck {
+            scale: Scale {
+                title: Some(&title),
+                root: 9,
+                intervals: vec![0, 3, 5, 7, 10],
+                frets: 0..=24,
+                hand: Hand::Left,
+                mode: Mode::Dark,
+                use_background: true,
+                ..Default::default()
+            },
+            shape: None,
+        };
+        let image = generate_neck_svg(&neck);
+        let expected = std::fs::read_to_string("fixtures/neck/13832933890756283203.svg")
+            .expect("couldn't open fixture");
+        assert_eq!(image.unwrap(), expected);
+    }
+
+    fn rasterise(svg: &str) -> Vec<u8> {
+        let mut options = resvg::usvg::Options::default();
+        options
+            .fontdb_mut()
+            .load_font_file("fixtures/fonts/DejaVuSans-subset.ttf")
+            .unwrap();
+        let tree = resvg::usvg::Tree::from_str(svg, &options).unwrap();
+        let size = tree.size().to_int_size();
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height()).unwrap();
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        pixmap.take()
+    }
+
+    #[test]
+    fn compact_should_draw_the_same() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chords = [
+            (
+                frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+                Mode::Light,
+                false,
+            ),
+            (frets_from_numbers(&[1, 3, 3, 2, 1, 1]), Mode::Dark, true),
+            (
+                vec![
+                    Fret::Unplayed,
+                    Fret::Optional(3),
+                    Fret::Fretted(2),
+                    Fret::Open,
+                    Fret::Fretted(1),
+                    Fret::Optional(0),
+                ],
+                Mode::Light,
+                true,
+            ),
+        ];
+        for (frets, mode, use_background) in chords {
+            let chord = || Chord {
+                title: Some(&title),
+                suffix: Some(&suffix),
+                frets: frets.clone(),
+                mode,
+                use_background,
+                show_difficulty: true,
+                ..Default::default()
+            };
+            let full = Renderer::default().generate_svg(chord()).unwrap();
+            let compact = Renderer::default().compact().generate_svg(chord()).unwrap();
+            assert!(compact.len() < full.len());
+            assert!(rasterise(&full) == rasterise(&compact));
+        }
+    }
+
+    // resvg doesn't do custom properties, so swap in the values they'd have
+    fn resolve_css_variables(svg: &str, mode: Mode) -> String {
+        let palette = get_palette(mode);
+        svg.replace(
+            "var(--chord-root, var(--chord-dot, var(--chord-fg)))",
+            palette.fg,
+        )
+        .replace("var(--chord-dot, var(--chord-fg))", palette.fg)
+        .replace("var(--chord-fg)", palette.fg)
+        .replace("var(--chord-bg)", palette.bg)
+    }
+
+    #[test]
+    fn css_variables_should_draw_the_same() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chords = [
+            (
+                frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+                Mode::Light,
+                false,
+            ),
+            (frets_from_numbers(&[1, 3, 3, 2, 1, 1]), Mode::Dark, true),
+            (
+                vec![
+                    Fret::Unplayed,
+                    Fret::Optional(6),
+                    Fret::Fretted(5),
+                    Fret::Open,
+                    Fret::Fretted(4),
+                    Fret::Optional(0),
+                ],
+                Mode::Light,
+                true,
+            ),
+        ];
+        for (frets, mode, use_background) in chords {
+            let chord = || Chord {
+                title: Some(&title),
+                suffix: Some(&suffix),
+                frets: frets.clone(),
+                mode,
+                use_background,
+                show_difficulty: true,
+                ..Default::default()
+            };
+            let inline = Renderer::default().generate_svg(chord()).unwrap();
+            let css = Renderer::default()
+                .css_variables()
+                .generate_svg(chord())
+                .unwrap();
+            // only in the custom property
+            assert_eq!(css.matches(get_palette(mode).fg).count(), 1);
+            assert!(rasterise(&inline) == rasterise(&resolve_css_variables(&css, mode)));
+        }
+    }
+
+    #[test]
+    fn auto_mode_should_draw_light_by_default() {
+        let title = String::from("E♭");
+        let suffix = String::from("7");
+        let chord = |mode| Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 6, 5, 6, -1, -1]),
+            use_background: true,
+            mode,
+            ..Default::default()
+        };
+        let light = generate_svg(chord(Mode::Light)).unwrap();
+        let auto = generate_svg(chord(Mode::Auto)).unwrap();
+        assert!(auto.contains("@media (prefers-color-scheme: dark)"));
+        assert!(rasterise(&light) == rasterise(&resolve_css_variables(&auto, Mode::Light)));
+    }
 }
 
 // ♭ \u266D
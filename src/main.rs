@@ -1,6 +1,11 @@
 use chord_gen::{
-    render_svg,
-    types::{Chord, Hand, Mode},
+    caged, chord_tones, inversions, name_chord, parse_chord, pitch_class, render_midi, render_wav,
+    resolve_numeral, resolve_progression, scale_intervals, split_note, to_english, transpose,
+    types::{
+        position, Accidentals, Chord, Footer, Fret, Hand, Mode, Neck, NoteNaming, Scale, Strum,
+        StrumDirection, Tuning,
+    },
+    voice_progression, Font, Renderer, VoiceLeading, Voicing,
 };
 use clap::{arg, Command};
 
@@ -17,29 +22,73 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .version("2.1.1")
         .author("James Baum <james@jamesbaum.co.uk>")
         .about("Creates guitar chord diagrams")
-        .arg(arg!(-f --frets <FRETS> "Notes to fret, 6 comma-separated values. 0 for open string, -1 to skip a string.")) // comma-separated string x,x,0,2,3,2
-        .arg(arg!(-p --fingers <FINGERS> "Suggested fingering, 6 comma-separated values. 0 for open string, x to skip a string.")) // comma-separated string x,x,0,2,3,1
+        .arg(arg!(-f --frets <FRETS> "Notes to fret, 6 comma-separated values. 0 for open string, x or -1 to mute a string, - to leave it unplayed, brackets for optional notes eg (2).")) // comma-separated string x,x,0,2,3,2
+        .arg(arg!(-p --fingers <FINGERS> "Suggested fingering, 6 comma-separated values. 0 for open string, x to skip a string, T for thumb. Optional, worked out from frets if missing.")) // comma-separated string x,x,0,2,3,1
         .arg(arg!(-t --title <TITLE> "Name of chord. Optional."))
         .arg(arg!(-s --suffix <SUFFIX> "Chord suffix to use in title. Optional."))
         .arg(arg!(-d --hand <HANDEDNESS> "Left or right handedness. `left` or `right`. Optional, defaults to right."))
-        .arg(arg!(-r --barres <BARRES> "Frets which should be barred. Comma-separated string. Optional."))
-        .arg(arg!(-m --mode <MODE> "Light or dark mode `light` or `dark`. Optional, defaults to light."))
+        .arg(arg!(-r --barres <BARRES> "Frets which should be barred. Comma-separated string. Optional, worked out from frets and fingers if missing."))
+        .arg(arg!(--"manual-barres" "Only draw barres passed with --barres. Optional."))
+        .arg(arg!(-m --mode <MODE> "Light or dark mode `light`, `dark` or `auto` to follow the reader's preference. Optional, defaults to light."))
         .arg(arg!(-b --background "Add a background to image. Optional."))
+        .arg(arg!(--difficulty "Show a 1-5 difficulty badge. Optional."))
+        .arg(arg!(-o --format <FORMAT> "Output format `svg`, `mid` or `wav`. Optional, defaults to svg."))
+        .arg(arg!(--strum <DIRECTION> "Strum direction for audio output `down`, `up` or `block`. Optional, defaults to block."))
+        .arg(arg!(--"strum-delay" <MS> "Milliseconds between strings when strumming, up to 2000. Needs --strum. Optional, defaults to 30.").requires("strum"))
+        .arg(arg!(--tempo <BPM> "Tempo for audio output, from 4 to 1000. The chord rings for one bar. Optional, defaults to 120."))
+        .arg(arg!(--footer <TEXT> "Text along the bottom of the diagram. Optional, defaults to chordgenerator.xyz."))
+        .arg(arg!(--"footer-link" <URL> "Link the footer text to a URL. Optional."))
+        .arg(arg!(--"no-footer" "Leave out the footer and crop the diagram to fit. Optional."))
+        .arg(arg!(--font <PATH> "Draw text as paths using this TTF or OTF font, so the diagram looks the same everywhere. Optional."))
+        .arg(arg!(--"css-variables" "Colour the diagram with CSS custom properties, so pages can restyle it. Optional."))
+        .arg(arg!(--compact "Minify the SVG. It draws the same, but is smaller. Optional."))
+        .arg(arg!(--scale <SCALE> "Draw a scale, mode or arpeggio instead of a chord, eg `dorian`, `minor pentatonic`, `m7 arpeggio` or a formula like `1 b3 5`. Optional."))
+        .arg(arg!(--root <NOTE> "Root note of the scale. Optional, defaults to the title."))
+        .arg(arg!(--position <FRET> "First fret of a five fret scale position. Optional, defaults to 0."))
+        .arg(arg!(--range <FRETS> "Frets to show for a scale, eg `0-12`. Optional, used instead of --position."))
+        .arg(arg!(--neck <FRETS> "Draw the chord's notes, or the scale's, along a sideways neck this many frets long, from 12 to 24. The chord's shape is picked out if frets are given. Optional."))
+        .arg(arg!(--template <PATH> "Tera template to draw the SVG with. Optional, defaults to the built-in template."))
+        .arg(arg!(--"note-names" "Name the note on each dot. Optional."))
+        .arg(arg!(--spelling "Show each string's note and interval under the grid. Optional."))
+        .arg(arg!(--"tuning-labels" "Name each string's open note under the grid, in the --naming system. Optional."))
+        .arg(arg!(--accidentals <ACCIDENTALS> "Name notes with `sharps` or `flats` rather than spelling them from the key or chord. Optional."))
+        .arg(arg!(--name "Name the chord from its notes when there's no title, eg `C/E`. Optional."))
+        .arg(arg!(--naming <SYSTEM> "Write notes as `english`, `german` (H for B), `solfege` (Do Re Mi) or `nashville` numbers from the --key. Notes you give are read the same way. Optional, defaults to english."))
+        .arg(arg!(--key <KEY> "Key to read Roman numerals and Nashville numbers in, eg `G` or `Em`. Needed with `--naming nashville`, and `progression` shows the numerals under each chord. Optional.").global(true))
+        .subcommand(
+            Command::new("caged")
+                .about("Draws the five CAGED shapes of a major or minor chord, eg `G` or `F#m`")
+                .arg(arg!(<CHORD> "Major or minor chord, eg `G`, `Bb` or `Am`")),
+        )
+        .subcommand(
+            Command::new("inversions")
+                .about("Draws every inversion of a chord on a set of strings, eg `Cmaj7 --strings D-G-B-E`")
+                .arg(arg!(<CHORD> "Chord, eg `Cmaj7`, `Am7` or `Bm7b5`"))
+                .arg(arg!(--strings <STRINGS> "Strings to play on, low to high, one for each note, eg `D-G-B-E`").required(true))
+                .arg(arg!(--voicing <VOICING> "`close`, `drop2` or `drop3`. Optional, defaults to close.")),
+        )
+        .subcommand(
+            Command::new("progression")
+                .about("Draws a row of chords voiced to move as little as possible, eg `\"Am7 D7 Gmaj7 Cmaj7\"`")
+                .arg(arg!(<CHORDS> "Chords separated by spaces, or Roman numerals or Nashville numbers with --key, eg `\"I vi IV V\"`"))
+                .arg(arg!(--transpose <SEMITONES> "Move the chords up this many semitones, or down if negative. Optional."))
+                .arg(arg!(--range <FRETS> "Frets to play within, eg `5-10`. Optional, defaults to 0-12."))
+                .arg(arg!(--"max-difficulty" <GRADE> "Hardest shapes to use, 1 to 5. Optional, defaults to 3.")),
+        )
         .get_matches();
 
     let default_frets = "x,x,x,x,x,x".to_string();
-    let frets: Vec<i32> = matches
+    let frets: Vec<Fret> = matches
         .get_one::<String>("frets")
         .unwrap_or(&default_frets)
         .split(',')
-        .map(|letter| letter.parse::<i32>().unwrap_or(-1))
+        .map(|letter| letter.parse::<Fret>().unwrap_or(Fret::Muted))
         .collect();
 
-    let fingers: Vec<&str> = matches
-        .get_one::<String>("fingers")
-        .unwrap_or(&default_frets)
-        .split(',')
-        .collect();
+    let fingers: Vec<&str> = match matches.get_one::<String>("fingers") {
+        Some(fingers) => fingers.split(',').collect(),
+        None => vec![],
+    };
 
     let barres: Option<Vec<i32>> = matches.get_one::<String>("barres").map(|frets| {
         frets
@@ -54,16 +103,80 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             hand = Hand::Left;
         }
     }
-    let mut mode = Mode::Light;
-    if let Some(m) = matches.get_one::<String>("mode") {
-        if m == "dark" {
-            mode = Mode::Dark;
+    let mode = match matches.get_one::<String>("mode").map(|m| m.as_str()) {
+        Some("dark") => Mode::Dark,
+        Some("auto") => Mode::Auto,
+        _ => Mode::Light,
+    };
+    let accidentals = match matches.get_one::<String>("accidentals") {
+        Some(accidentals) => accidentals
+            .parse::<Accidentals>()
+            .map_err(|_| "accidentals should be `sharps` or `flats`")?,
+        None => Accidentals::Auto,
+    };
+    let naming = match matches.get_one::<String>("naming") {
+        Some(naming) => naming
+            .parse::<NoteNaming>()
+            .map_err(|_| "naming should be `english`, `german`, `solfege` or `nashville`")?,
+        None => NoteNaming::English,
+    };
+    // the key is a note name, so in English when the notes are numbers
+    let key = match (naming, matches.get_one::<String>("key")) {
+        (NoteNaming::Nashville(_), None) => {
+            return Err("nashville numbers need a --key, eg --key G".into())
         }
-    }
-    let title = matches.get_one::<String>("title");
+        (NoteNaming::Nashville(_), Some(key)) => Some(key.clone()),
+        (naming, Some(key)) => Some(to_english(key, naming).unwrap_or_else(|| key.clone())),
+        (_, None) => None,
+    };
+    // Nashville numbers count from the key
+    let naming = match (naming, &key) {
+        (NoteNaming::Nashville(_), Some(key)) => {
+            NoteNaming::Nashville(pitch_class(key).ok_or("unknown key, eg G or Em")?)
+        }
+        (naming, _) => naming,
+    };
+    // notes are read into English names, which are used until the diagram
+    // is drawn
+    let read = |symbol: &str| {
+        let numeral = match (naming, &key) {
+            (NoteNaming::Nashville(_), Some(key)) => resolve_numeral(symbol, key),
+            _ => None,
+        };
+        numeral
+            .or_else(|| to_english(symbol, naming))
+            .unwrap_or_else(|| symbol.to_string())
+    };
+
+    let typed_title = matches.get_one::<String>("title").map(|title| read(title));
     let suffix = matches.get_one::<String>("suffix");
+    let note_names = matches.get_one::<bool>("note-names").unwrap_or(&false);
+    let show_spelling = matches.get_one::<bool>("spelling").unwrap_or(&false);
+    let show_tuning = matches.get_one::<bool>("tuning-labels").unwrap_or(&false);
 
     let use_background = matches.get_one::<bool>("background").unwrap_or(&false);
+    let show_difficulty = matches.get_one::<bool>("difficulty").unwrap_or(&false);
+    let manual_barres = matches.get_one::<bool>("manual-barres").unwrap_or(&false);
+
+    let mut strum = Strum::default();
+    if let Some(direction) = matches.get_one::<String>("strum") {
+        strum.direction = direction.parse::<StrumDirection>().unwrap_or_default();
+        strum.delay = match matches.get_one::<String>("strum-delay") {
+            Some(delay) => delay
+                .parse::<u32>()
+                .ok()
+                .filter(|delay| *delay <= 2000)
+                .ok_or("strum-delay should be a number of ms from 0 to 2000")?,
+            None => 30,
+        };
+    }
+    if let Some(tempo) = matches.get_one::<String>("tempo") {
+        strum.tempo = tempo
+            .parse::<u32>()
+            .ok()
+            .filter(|tempo| (4..=1000).contains(tempo))
+            .ok_or("tempo should be a number of bpm from 4 to 1000")?;
+    }
 
     // examples
     // cargo run -- -f "x,0,2,2,2,0" -p "x,0,2,1,3,0" -t "A" -d "left"
@@ -72,8 +185,43 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // cargo run -- -f "x,7,6,7,8,x" -p "x,2,1,3,4,x" -t "Hendrix" -d "right"
     // cargo run -- -f "4,3,1,1,1,x" -p "3,2,1,1,1,x" -t "Broken"
 
+    let footer = match (
+        matches.get_one::<String>("footer"),
+        matches.get_one::<String>("footer-link"),
+    ) {
+        _ if *matches.get_one::<bool>("no-footer").unwrap_or(&false) => Footer::None,
+        (Some(text), Some(href)) => Footer::Link {
+            text: text.clone(),
+            href: href.clone(),
+        },
+        (None, Some(href)) => Footer::Link {
+            text: href.clone(),
+            href: href.clone(),
+        },
+        (Some(text), None) => Footer::Text(text.clone()),
+        (None, None) => Footer::Default,
+    };
+
     let output_dir = "./output/";
 
+    // eg "Am7" as "A" and the suffix "m7"
+    let named = match typed_title {
+        None if *matches.get_one::<bool>("name").unwrap_or(&false) => {
+            let name = name_chord(&frets, &Tuning::default(), accidentals)
+                .ok_or("couldn't name the chord, give it a --title")?;
+            let (note, quality) = split_note(&name);
+            Some((note.to_string(), quality.to_string()))
+        }
+        _ => None,
+    };
+    let title = typed_title
+        .as_ref()
+        .or(named.as_ref().map(|(note, _)| note));
+    let suffix = suffix.or(named
+        .as_ref()
+        .map(|(_, quality)| quality)
+        .filter(|quality| !quality.is_empty()));
+
     let chord = Chord {
         frets,
         fingers,
@@ -83,9 +231,273 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         mode,
         use_background: *use_background,
         barres,
+        strum,
+        show_difficulty: *show_difficulty,
+        manual_barres: *manual_barres,
+        footer,
+        note_names: *note_names,
+        show_spelling: *show_spelling,
+        show_tuning: *show_tuning,
+        accidentals,
+        naming,
+        ..Default::default()
     };
 
-    let filename = render_svg(chord, output_dir)?;
+    let renderer = || -> Result<Renderer, Box<dyn std::error::Error>> {
+        let mut renderer = match matches.get_one::<String>("template") {
+            Some(template) => Renderer::with_template(template)?,
+            None => Renderer::default(),
+        };
+        if *matches.get_one::<bool>("css-variables").unwrap_or(&false) {
+            renderer = renderer.css_variables();
+        }
+        if let Some(font) = matches.get_one::<String>("font") {
+            renderer = renderer.with_font(Font::from_file(font)?);
+        }
+        if *matches.get_one::<bool>("compact").unwrap_or(&false) {
+            renderer = renderer.compact();
+        }
+        Ok(renderer)
+    };
+
+    // cargo run -- -b caged Am
+    if let Some(("caged", caged_matches)) = matches.subcommand() {
+        let name = read(caged_matches.get_one::<String>("CHORD").expect("required"));
+        let (root, intervals) = parse_chord(&name).ok_or("unknown chord, eg G or F#m")?;
+        let (note, _) = split_note(&name);
+        let minor = match intervals.as_slice() {
+            [0, 4, 7] => false,
+            [0, 3, 7] => true,
+            _ => return Err("only major and minor chords have CAGED shapes".into()),
+        };
+        let title = note.to_string();
+        let suffix = String::from("m");
+        for shape in caged(root, minor) {
+            let label = format!("{} shape", shape.shape);
+            let chord = Chord {
+                hand,
+                mode,
+                use_background: *use_background,
+                note_names: *note_names,
+                show_spelling: *show_spelling,
+                show_tuning: *show_tuning,
+                accidentals,
+                naming,
+                // label each shape unless a footer was asked for
+                footer: match chord.footer {
+                    Footer::Default => Footer::Text(label.clone()),
+                    ref footer => footer.clone(),
+                },
+                ..shape.chord(Some(&title), minor.then_some(&suffix))
+            };
+            println!("{}: {}", label, renderer()?.render_svg(chord, output_dir)?);
+        }
+        return Ok(());
+    }
+
+    // cargo run -- -b progression "Am7 D7 Gmaj7 Cmaj7" --range 5-10
+    if let Some(("progression", progression_matches)) = matches.subcommand() {
+        let mut limits = VoiceLeading::default();
+        if let Some(range) = progression_matches.get_one::<String>("range") {
+            let (first, last) = range.split_once('-').ok_or("range should be eg 5-10")?;
+            limits.frets = first.trim().parse::<i32>()?..=last.trim().parse::<i32>()?;
+        }
+        if let Some(grade) = progression_matches.get_one::<String>("max-difficulty") {
+            limits.max_grade = grade.parse::<u8>()?;
+        }
+        let progression = progression_matches
+            .get_one::<String>("CHORDS")
+            .expect("required");
+        // numerals are kept to show under each chord
+        let (symbols, numerals): (Vec<String>, Vec<Option<String>>) = match &key {
+            Some(key) => (
+                resolve_progression(progression, key)?,
+                progression
+                    .split_whitespace()
+                    .map(|numeral| Some(numeral.to_string()))
+                    .collect(),
+            ),
+            None => (
+                progression.split_whitespace().map(read).collect(),
+                vec![None; progression.split_whitespace().count()],
+            ),
+        };
+        let semitones = match progression_matches.get_one::<String>("transpose") {
+            Some(semitones) => semitones.parse::<i32>()?,
+            None => 0,
+        };
+        let symbols = if semitones != 0 || accidentals != Accidentals::Auto {
+            symbols
+                .iter()
+                .map(|symbol| transpose(symbol, semitones, accidentals))
+                .collect::<Option<Vec<String>>>()
+                .ok_or("only chords can be transposed")?
+        } else {
+            symbols
+        };
+        let voicings = voice_progression(&symbols.join(" "), &limits)?;
+
+        let names: Vec<(String, String)> = symbols
+            .iter()
+            .map(|symbol| {
+                let (note, quality) = split_note(symbol);
+                (note.to_string(), quality.to_string())
+            })
+            .collect();
+        let chords: Vec<Chord> = voicings
+            .iter()
+            .zip(names.iter())
+            .zip(numerals.iter())
+            .map(|((voicing, (title, suffix)), numeral)| Chord {
+                hand,
+                mode,
+                use_background: *use_background,
+                footer: chord.footer.clone(),
+                note_names: *note_names,
+                show_spelling: *show_spelling,
+                show_tuning: *show_tuning,
+                accidentals,
+                naming,
+                ..voicing.chord(
+                    Some(title),
+                    (!suffix.is_empty()).then_some(suffix),
+                    numeral.as_ref(),
+                )
+            })
+            .collect();
+        println!("{}", renderer()?.render_row_svg(chords, output_dir)?);
+        return Ok(());
+    }
+
+    // cargo run -- inversions Cmaj7 --strings D-G-B-E --voicing drop2
+    if let Some(("inversions", inversion_matches)) = matches.subcommand() {
+        let name = read(
+            inversion_matches
+                .get_one::<String>("CHORD")
+                .expect("required"),
+        );
+        let (root, intervals) = parse_chord(&name).ok_or("unknown chord, eg Cmaj7 or Am7")?;
+        let voicing = match inversion_matches
+            .get_one::<String>("voicing")
+            .map(|voicing| voicing.as_str())
+        {
+            Some("drop2") => Voicing::Drop2,
+            Some("drop3") => Voicing::Drop3,
+            Some("close") | None => Voicing::Close,
+            _ => return Err("voicing should be `close`, `drop2` or `drop3`".into()),
+        };
+        let tuning = Tuning::default();
+        // each name is the next string up tuned to that note, so E-D-G-B
+        // starts on the low E
+        let mut strings: Vec<usize> = vec![];
+        for string_name in inversion_matches
+            .get_one::<String>("strings")
+            .expect("required")
+            .split(['-', ','])
+        {
+            let note =
+                pitch_class(&read(string_name)).ok_or("strings should be named, eg D-G-B-E")?;
+            let next = strings.last().map(|last| last + 1).unwrap_or(0);
+            let string = (next..tuning.0.len())
+                .find(|string| tuning.0[*string] % 12 == note)
+                .ok_or("strings should be named low to high, eg D-G-B-E")?;
+            strings.push(string);
+        }
+
+        let found = inversions(root, &intervals, voicing, &strings, &tuning);
+        if found.is_empty() {
+            return Err("the chord needs one string for each note".into());
+        }
+        let (note, quality) = split_note(&name);
+        let title = note.to_string();
+        let suffix = quality.to_string();
+        for inversion in found {
+            let label = inversion.label();
+            let chord = Chord {
+                hand,
+                mode,
+                use_background: *use_background,
+                footer: chord.footer.clone(),
+                note_names: *note_names,
+                show_spelling: *show_spelling,
+                show_tuning: *show_tuning,
+                accidentals,
+                naming,
+                ..inversion.chord(
+                    Some(&title),
+                    (!suffix.is_empty()).then_some(&suffix),
+                    Some(&label),
+                )
+            };
+            println!("{}: {}", label, renderer()?.render_svg(chord, output_dir)?);
+        }
+        return Ok(());
+    }
+
+    // cargo run -- --scale "minor pentatonic" -t "A minor pentatonic" --position 5
+    let scale = match matches.get_one::<String>("scale") {
+        Some(scale) => {
+            let intervals = scale_intervals(scale).ok_or("unknown scale or formula")?;
+            let root = matches
+                .get_one::<String>("root")
+                .map(|root| read(root))
+                .or(title.cloned())
+                .and_then(|note| pitch_class(&note))
+                .ok_or("scales need a --root note")?;
+            let frets = match matches.get_one::<String>("range") {
+                Some(range) => {
+                    let (first, last) = range.split_once('-').ok_or("range should be eg 0-12")?;
+                    let (first, last) = (first.trim().parse::<i32>()?, last.trim().parse::<i32>()?);
+                    if first > last {
+                        return Err("range should run from the lower fret, eg 5-10".into());
+                    }
+                    first..=last
+                }
+                None => position(
+                    matches
+                        .get_one::<String>("position")
+                        .and_then(|fret| fret.parse::<i32>().ok())
+                        .unwrap_or(0),
+                ),
+            };
+            Some(Scale {
+                title,
+                root,
+                intervals,
+                frets,
+                hand,
+                mode,
+                use_background: *use_background,
+                footer: chord.footer.clone(),
+                ..Default::default()
+            })
+        }
+        None => None,
+    };
+
+    // cargo run -- -f "x,3,2,0,1,0" -t "C" --neck 12
+    if let Some(frets) = matches.get_one::<String>("neck") {
+        let mut notes = scale.unwrap_or_else(|| chord_tones(&chord));
+        notes.frets = 0..=frets
+            .parse::<i32>()
+            .map_err(|_| "--neck should be a number of frets, 12 to 24")?;
+        let neck = Neck {
+            scale: notes,
+            shape: matches.contains_id("frets").then_some(chord),
+        };
+        println!("{}", renderer()?.render_neck_svg(&neck, output_dir)?);
+        return Ok(());
+    }
+    if let Some(scale) = scale {
+        println!("{}", renderer()?.render_scale_svg(&scale, output_dir)?);
+        return Ok(());
+    }
+
+    let filename = match matches.get_one::<String>("format").map(|f| f.as_str()) {
+        Some("mid") => render_midi(&chord, output_dir)?,
+        Some("wav") => render_wav(&chord, output_dir)?,
+        _ => renderer()?.render_svg(chord, output_dir)?,
+    };
     println!("{}", filename);
 
     Ok(())
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::types::{Accidentals, Chord, Footer, Fret, Hand, Mode, NoteNaming, Strum, Tuning};
+use crate::utils::escape_xml;
+
+/// Owned copy of a `Chord`, as embedded in generated SVGs
+#[derive(PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChordDefinition {
+    pub frets: Vec<Fret>,
+    pub fingers: Vec<String>,
+    pub title: Option<String>,
+    pub suffix: Option<String>,
+    pub hand: Hand,
+    pub mode: Mode,
+    pub use_background: bool,
+    pub barres: Option<Vec<i32>>,
+    #[serde(skip_serializing_if = "is_default")]
+    pub tuning: Tuning,
+    #[serde(skip_serializing_if = "is_default")]
+    pub strum: Strum,
+    #[serde(skip_serializing_if = "is_default")]
+    pub show_difficulty: bool,
+    #[serde(skip_serializing_if = "is_default")]
+    pub manual_barres: bool,
+    #[serde(skip_serializing_if = "is_default")]
+    pub footer: Footer,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    #[serde(skip_serializing_if = "is_default")]
+    pub note_names: bool,
+    #[serde(skip_serializing_if = "is_default")]
+    pub accidentals: Accidentals,
+    #[serde(skip_serializing_if = "is_default")]
+    pub naming: NoteNaming,
+    #[serde(skip_serializing_if = "is_default")]
+    pub show_spelling: bool,
+    #[serde(skip_serializing_if = "is_default")]
+    pub show_tuning: bool,
+}
+
+// fields added since 2.1 are left out unless set, which keeps the metadata
+// of an ordinary chord short
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl ChordDefinition {
+    /// Borrows the definition as a `Chord` to render it again
+    pub fn chord(&self) -> Chord<'_> {
+        Chord {
+            frets: self.frets.clone(),
+            fingers: self.fingers.iter().map(|finger| finger.as_str()).collect(),
+            title: self.title.as_ref(),
+            hand: self.hand,
+            suffix: self.suffix.as_ref(),
+            mode: self.mode,
+            use_background: self.use_background,
+            barres: self.barres.clone(),
+            tuning: self.tuning.clone(),
+            strum: self.strum,
+            show_difficulty: self.show_difficulty,
+            manual_barres: self.manual_barres,
+            footer: self.footer.clone(),
+            subtitle: self.subtitle.as_ref(),
+            note_names: self.note_names,
+            accidentals: self.accidentals,
+            naming: self.naming,
+            show_spelling: self.show_spelling,
+            show_tuning: self.show_tuning,
+        }
+    }
+}
+
+impl From<&Chord<'_>> for ChordDefinition {
+    fn from(chord: &Chord) -> Self {
+        ChordDefinition {
+            frets: chord.frets.clone(),
+            fingers: chord
+                .fingers
+                .iter()
+                .map(|finger| finger.to_string())
+                .collect(),
+            title: chord.title.cloned(),
+            suffix: chord.suffix.cloned(),
+            hand: chord.hand,
+            mode: chord.mode,
+            use_background: chord.use_background,
+            barres: chord.barres.clone(),
+            tuning: chord.tuning.clone(),
+            strum: chord.strum,
+            show_difficulty: chord.show_difficulty,
+            manual_barres: chord.manual_barres,
+            footer: chord.footer.clone(),
+            subtitle: chord.subtitle.cloned(),
+            note_names: chord.note_names,
+            accidentals: chord.accidentals,
+            naming: chord.naming,
+            show_spelling: chord.show_spelling,
+            show_tuning: chord.show_tuning,
+        }
+    }
+}
+
+/// Chord definition as escaped JSON, for a `<metadata>` element
+pub fn chord_metadata(chord: &Chord) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_string(&ChordDefinition::from(chord))?;
+    Ok(escape_xml(&json))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+/// Reads the chord definition back out of a generated SVG
+pub fn read_svg_metadata(svg: &str) -> Result<ChordDefinition, Box<dyn Error>> {
+    let missing = || io::Error::new(io::ErrorKind::InvalidData, "no chord metadata in SVG");
+
+    let element = svg.find("<metadata").ok_or_else(missing)?;
+    let start = element + svg[element..].find('>').ok_or_else(missing)? + 1;
+    let end = start + svg[start..].find("</metadata>").ok_or_else(missing)?;
+
+    let json = unescape_xml(svg[start..end].trim());
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Reads the chord definition from an SVG file made by `render_svg`
+pub fn parse_svg_metadata<P: AsRef<Path>>(path: P) -> Result<ChordDefinition, Box<dyn Error>> {
+    read_svg_metadata(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate_svg,
+        metadata::{chord_metadata, parse_svg_metadata, read_svg_metadata, ChordDefinition},
+        types::{frets_from_numbers, Chord, Footer, Fret, Hand, Mode, Strum, StrumDirection},
+        utils::get_filename,
+    };
+
+    #[test]
+    fn should_write_metadata() {
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            fingers: vec!["x", "0", "2", "3", "1", "0"],
+            ..Default::default()
+        };
+        assert_eq!(
+            chord_metadata(&chord).unwrap(),
+            "{&quot;frets&quot;:[&quot;x&quot;,&quot;0&quot;,&quot;2&quot;,&quot;2&quot;,&quot;1&quot;,&quot;0&quot;],&quot;fingers&quot;:[&quot;x&quot;,&quot;0&quot;,&quot;2&quot;,&quot;3&quot;,&quot;1&quot;,&quot;0&quot;],&quot;title&quot;:&quot;A&quot;,&quot;suffix&quot;:&quot;m&quot;,&quot;hand&quot;:&quot;right&quot;,&quot;mode&quot;:&quot;light&quot;,&quot;use_background&quot;:false,&quot;barres&quot;:null}"
+        );
+    }
+
+    #[test]
+    fn should_round_trip_chord() {
+        let title = String::from("<Tom & \"Jerry\">");
+        let suffix = String::from("♭9");
+        let subtitle = String::from("Drop 2");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: vec![
+                Fret::Unplayed,
+                Fret::Muted,
+                Fret::Open,
+                Fret::Fretted(2),
+                Fret::Optional(3),
+                Fret::Optional(0),
+            ],
+            hand: Hand::Left,
+            mode: Mode::Dark,
+            use_background: true,
+            barres: Some(vec![2]),
+            strum: Strum {
+                direction: StrumDirection::Up,
+                delay: 20,
+                tempo: 90,
+            },
+            show_difficulty: true,
+            subtitle: Some(&subtitle),
+            footer: Footer::Link {
+                text: String::from("example.com"),
+                href: String::from("https://example.com"),
+            },
+            ..Default::default()
+        };
+        let svg = format!(
+            "<svg><metadata>{}</metadata></svg>",
+            chord_metadata(&chord).unwrap()
+        );
+        let definition = read_svg_metadata(&svg).unwrap();
+
+        assert_eq!(definition, ChordDefinition::from(&chord));
+        assert_eq!(get_filename(&definition.chord()), get_filename(&chord));
+    }
+
+    #[test]
+    fn should_parse_fixture() {
+        let definition = parse_svg_metadata("fixtures/markers/929495571736910104.svg").unwrap();
+        assert_eq!(definition.title, Some(String::from("C")));
+        assert_eq!(definition.suffix, Some(String::from("/G")));
+        assert_eq!(
+            definition.frets,
+            vec![
+                Fret::Optional(3),
+                Fret::Fretted(3),
+                Fret::Fretted(2),
+                Fret::Open,
+                Fret::Fretted(1),
+                Fret::Optional(0),
+            ]
+        );
+        // fingers and barres are filled in, so it draws the same diagram
+        assert_eq!(definition.fingers, vec!["3", "4", "2", "0", "1", "0"]);
+        assert_eq!(
+            generate_svg(definition.chord()).unwrap(),
+            std::fs::read_to_string("fixtures/markers/929495571736910104.svg").unwrap()
+        );
+    }
+
+    #[test]
+    fn should_error_without_metadata() {
+        assert!(read_svg_metadata("<svg></svg>").is_err());
+        assert!(read_svg_metadata("<svg><metadata>{</metadata></svg>").is_err());
+    }
+}
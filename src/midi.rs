@@ -0,0 +1,250 @@
+use crate::{types::Chord, utils::get_strum_offsets};
+
+// at 120bpm one tick = 1ms
+const TICKS_PER_QUARTER: u32 = 500;
+const NOTE_LENGTH: u32 = TICKS_PER_QUARTER * 4; // one bar of 4/4
+const VELOCITY: u8 = 80;
+const STEEL_GUITAR: u8 = 25;
+const HIGHEST_NOTE: u8 = 127;
+// delta times are at most four bytes of seven bits
+const LAST_TICK: u64 = 0x0fff_ffff - NOTE_LENGTH as u64;
+
+/// MIDI note numbers for each played string, low E first, kept within the
+/// 0 to 127 MIDI allows
+pub fn get_midi_notes(chord: &Chord) -> Vec<u8> {
+    chord
+        .frets
+        .iter()
+        .zip(chord.tuning.0.iter())
+        .filter_map(|(fret, open)| {
+            fret.number().map(|fret| {
+                u8::try_from(fret.max(0))
+                    .ok()
+                    .and_then(|fret| open.checked_add(fret))
+                    .map_or(HIGHEST_NOTE, |note| note.min(HIGHEST_NOTE))
+            })
+        })
+        .collect()
+}
+
+fn write_var_len(bytes: &mut Vec<u8>, value: u32) {
+    let mut buffer = vec![(value & 0x7f) as u8];
+    let mut value = value >> 7;
+    while value > 0 {
+        buffer.push(((value & 0x7f) as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.extend(buffer.iter().rev());
+}
+
+pub fn generate_midi(chord: &Chord) -> Vec<u8> {
+    let notes = get_midi_notes(chord);
+    // microseconds per quarter note, which only has three bytes, so the
+    // slowest tempo is about 3.6bpm
+    let quarter = (60_000_000 / chord.strum.tempo.max(1)).clamp(1, 0xff_ffff);
+    // strum delays are in ms
+    let offsets = get_strum_offsets(&chord.strum, notes.len())
+        .iter()
+        .map(|ms| {
+            let ticks = ms.saturating_mul(1000 * u64::from(TICKS_PER_QUARTER)) / u64::from(quarter);
+            ticks.min(LAST_TICK) as u32
+        })
+        .collect::<Vec<_>>();
+
+    // (time, status, note, velocity)
+    let mut events: Vec<(u32, u8, u8, u8)> = vec![];
+    for (note, offset) in notes.iter().zip(offsets.iter()) {
+        events.push((*offset, 0x90, *note, VELOCITY));
+        events.push((offset + NOTE_LENGTH, 0x80, *note, 0));
+    }
+    events.sort_by_key(|(time, ..)| *time);
+
+    let mut track: Vec<u8> = vec![];
+    // tempo
+    write_var_len(&mut track, 0);
+    track.extend([0xff, 0x51, 0x03]);
+    track.extend(&quarter.to_be_bytes()[1..]);
+    // instrument
+    write_var_len(&mut track, 0);
+    track.extend([0xc0, STEEL_GUITAR]);
+
+    let mut last_time = 0;
+    for (time, status, note, velocity) in events {
+        write_var_len(&mut track, time - last_time);
+        track.extend([status, note, velocity]);
+        last_time = time;
+    }
+    // end of track
+    write_var_len(&mut track, 0);
+    track.extend([0xff, 0x2f, 0x00]);
+
+    let mut bytes: Vec<u8> = vec![];
+    bytes.extend(b"MThd");
+    bytes.extend(6u32.to_be_bytes());
+    bytes.extend(0u16.to_be_bytes()); // single track
+    bytes.extend(1u16.to_be_bytes());
+    bytes.extend((TICKS_PER_QUARTER as u16).to_be_bytes());
+    bytes.extend(b"MTrk");
+    bytes.extend((track.len() as u32).to_be_bytes());
+    bytes.extend(track);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        midi::{generate_midi, get_midi_notes, write_var_len},
+        types::{frets_from_numbers, Chord, Strum, StrumDirection, Tuning},
+    };
+
+    #[test]
+    fn should_get_midi_notes() {
+        // open E
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 2, 2, 1, 0, 0]),
+            ..Default::default()
+        };
+        assert_eq!(get_midi_notes(&chord), vec![40, 47, 52, 56, 59, 64]);
+
+        // muted strings are skipped
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            ..Default::default()
+        };
+        assert_eq!(get_midi_notes(&chord), vec![45, 52, 57, 60, 64]);
+
+        // drop D
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 0, 0, 2, 3, 2]),
+            tuning: Tuning(vec![38, 45, 50, 55, 59, 64]),
+            ..Default::default()
+        };
+        assert_eq!(get_midi_notes(&chord), vec![38, 45, 50, 57, 62, 66]);
+
+        // too high for MIDI, or for a u8
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 90, 300, 2, 3, 2]),
+            ..Default::default()
+        };
+        assert_eq!(get_midi_notes(&chord), vec![40, 127, 127, 57, 62, 66]);
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 0, 0, 0, 0, 0]),
+            tuning: Tuning(vec![40, 45, 50, 55, 59, 250]),
+            ..Default::default()
+        };
+        assert_eq!(get_midi_notes(&chord)[5], 127);
+    }
+
+    #[test]
+    fn should_write_var_len() {
+        let mut bytes = vec![];
+        write_var_len(&mut bytes, 0);
+        write_var_len(&mut bytes, 0x7f);
+        write_var_len(&mut bytes, 0x80);
+        write_var_len(&mut bytes, 2000);
+        assert_eq!(bytes, vec![0x00, 0x7f, 0x81, 0x00, 0x8f, 0x50]);
+    }
+
+    #[test]
+    fn should_generate_midi_file() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 1, 0]),
+            strum: Strum {
+                direction: StrumDirection::Down,
+                delay: 30,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let expected: Vec<u8> = vec![
+            b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0x01, 0xf4, // header
+            b'M', b'T', b'r', b'k', 0, 0, 0, 31, // track
+            0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // tempo
+            0x00, 0xc0, 25, // instrument
+            0x00, 0x90, 60, 80, // C on
+            0x1e, 0x90, 64, 80, // E on
+            0x8f, 0x32, 0x80, 60, 0, // C off
+            0x1e, 0x80, 64, 0, // E off
+            0x00, 0xff, 0x2f, 0x00, // end
+        ];
+        assert_eq!(generate_midi(&chord), expected);
+    }
+
+    #[test]
+    fn should_scale_strum_to_tempo() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 1, 0]),
+            strum: Strum {
+                direction: StrumDirection::Up,
+                delay: 30,
+                tempo: 60,
+            },
+            ..Default::default()
+        };
+        let expected: Vec<u8> = vec![
+            0x00, 0xff, 0x51, 0x03, 0x0f, 0x42, 0x40, // tempo
+            0x00, 0xc0, 25, // instrument
+            0x00, 0x90, 64, 80, // E on
+            0x0f, 0x90, 60, 80, // C on
+            0x8f, 0x41, 0x80, 64, 0, // E off
+            0x0f, 0x80, 60, 0, // C off
+            0x00, 0xff, 0x2f, 0x00, // end
+        ];
+        assert_eq!(generate_midi(&chord)[22..], expected);
+    }
+
+    #[test]
+    fn should_not_overflow_long_strums() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+            strum: Strum {
+                direction: StrumDirection::Down,
+                delay: u32::MAX,
+                tempo: u32::MAX,
+            },
+            ..Default::default()
+        };
+        let midi = generate_midi(&chord);
+        assert_eq!(midi[midi.len() - 4..], [0x00, 0xff, 0x2f, 0x00]);
+    }
+
+    #[test]
+    fn should_keep_slow_tempos_within_three_bytes() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 1, 0]),
+            strum: Strum {
+                tempo: 1,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            generate_midi(&chord)[22..29],
+            [0x00, 0xff, 0x51, 0x03, 0xff, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn should_time_strums_at_the_written_tempo() {
+        // 1bpm is written as the slowest tempo, so a 100ms strum is 2 ticks
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, -1, -1, -1, 1, 0]),
+            strum: Strum {
+                direction: StrumDirection::Up,
+                delay: 100,
+                tempo: 1,
+            },
+            ..Default::default()
+        };
+        let expected: Vec<u8> = vec![
+            0x00, 0xff, 0x51, 0x03, 0xff, 0xff, 0xff, // tempo
+            0x00, 0xc0, 25, // instrument
+            0x00, 0x90, 64, 80, // E on
+            0x02, 0x90, 60, 80, // C on
+            0x8f, 0x4e, 0x80, 64, 0, // E off
+            0x02, 0x80, 60, 0, // C off
+            0x00, 0xff, 0x2f, 0x00, // end
+        ];
+        assert_eq!(generate_midi(&chord)[22..], expected);
+    }
+}
@@ -0,0 +1,403 @@
+use crate::utils::Palette;
+
+// element or text between elements
+#[derive(PartialEq, Debug)]
+enum Token<'a> {
+    Tag(&'a str),
+    Text(&'a str),
+}
+
+fn tokenise(svg: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = svg;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map(|end| end + 3).unwrap_or(rest.len());
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map(|end| end + 1).unwrap_or(rest.len());
+            tokens.push(Token::Tag(&rest[..end]));
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+pub(crate) struct Element {
+    pub(crate) name: String,
+    attributes: Vec<(String, String)>,
+    pub(crate) closing: bool, // </name>
+    self_closing: bool,       // <name />
+}
+
+impl Element {
+    pub(crate) fn parse(tag: &str) -> Self {
+        let inner = tag.trim_start_matches('<').trim_end_matches('>');
+        let closing = inner.starts_with('/');
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_start_matches('/').trim_end_matches('/');
+
+        let name_end = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_string();
+
+        let mut attributes = vec![];
+        let mut rest = inner[name_end..].trim_start();
+        while let Some(equals) = rest.find('=') {
+            let attribute = rest[..equals].trim().to_string();
+            let value = rest[equals + 1..].trim_start();
+            let Some(quote) = value.chars().next() else {
+                break;
+            };
+            let Some(end) = value[1..].find(quote) else {
+                break;
+            };
+            attributes.push((attribute, value[1..end + 1].to_string()));
+            rest = value[end + 2..].trim_start();
+        }
+
+        Element {
+            name,
+            attributes,
+            closing,
+            self_closing,
+        }
+    }
+
+    pub(crate) fn get(&self, attribute: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name == attribute)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn remove(&mut self, attribute: &str) -> Option<String> {
+        let index = self
+            .attributes
+            .iter()
+            .position(|(name, _)| name == attribute)?;
+        Some(self.attributes.remove(index).1)
+    }
+
+    pub(crate) fn add_class(&mut self, class: &str) {
+        match self.attributes.iter_mut().find(|(name, _)| name == "class") {
+            Some((_, classes)) => {
+                *classes += " ";
+                *classes += class;
+            }
+            None => self
+                .attributes
+                .push((String::from("class"), class.to_string())),
+        }
+    }
+
+    pub(crate) fn write(&self) -> String {
+        if self.closing {
+            return format!("</{}>", self.name);
+        }
+        let mut tag = format!("<{}", self.name);
+        for (name, value) in self.attributes.iter() {
+            tag += &format!(" {}=\"{}\"", name, value);
+        }
+        tag += if self.self_closing { "/>" } else { ">" };
+        tag
+    }
+}
+
+// attributes set to what they'd be anyway. font-weight is inherited, so
+// that's left to `minify`, which knows what's above each element.
+fn remove_defaults(element: &mut Element) {
+    if element.get("stroke-linecap") == Some("butt") {
+        element.remove("stroke-linecap");
+    }
+}
+
+// whether an element sets its own weight for the elements inside it
+fn sets_weight(element: &Element) -> bool {
+    element.get("font-weight").is_some()
+        || element
+            .get("style")
+            .is_some_and(|style| style.contains("font-weight"))
+}
+
+// palette colours moved into the stylesheet
+fn use_classes(element: &mut Element, palette: &Palette) {
+    let swaps = [
+        ("fill", palette.fg, "f"),
+        ("stroke", palette.fg, "s"),
+        ("fill", palette.bg, "b"),
+    ];
+    for (attribute, colour, class) in swaps {
+        if element.get(attribute) == Some(colour) {
+            element.remove(attribute);
+            element.add_class(class);
+        }
+    }
+}
+
+fn line_path(line: &Element) -> String {
+    let get = |name: &str| line.get(name).unwrap_or("0");
+    let (x1, y1, x2, y2) = (get("x1"), get("y1"), get("x2"), get("y2"));
+    if y1 == y2 {
+        format!("M{} {}H{}", x1, y1, x2)
+    } else if x1 == x2 {
+        format!("M{} {}V{}", x1, y1, y2)
+    } else {
+        format!("M{} {}L{} {}", x1, y1, x2, y2)
+    }
+}
+
+// neighbouring <line>s which look the same are drawn as one <path>
+fn merge_lines(elements: Vec<Element>) -> Vec<Element> {
+    let coordinates = ["x1", "y1", "x2", "y2"];
+    let style = |element: &Element| -> Vec<(String, String)> {
+        element
+            .attributes
+            .iter()
+            .filter(|(name, _)| !coordinates.contains(&name.as_str()))
+            .cloned()
+            .collect()
+    };
+
+    let mut merged: Vec<Element> = vec![];
+    let mut last_line_style: Option<Vec<(String, String)>> = None;
+    for element in elements {
+        if element.name != "line" || element.closing {
+            last_line_style = None;
+            merged.push(element);
+            continue;
+        }
+
+        let line_style = style(&element);
+        let path = line_path(&element);
+        match merged.last_mut() {
+            Some(previous) if last_line_style.as_ref() == Some(&line_style) => {
+                if let Some((_, d)) = previous.attributes.iter_mut().find(|(name, _)| name == "d") {
+                    *d += &path;
+                }
+            }
+            _ => {
+                let mut attributes = line_style.clone();
+                attributes.push((String::from("d"), path));
+                merged.push(Element {
+                    name: String::from("path"),
+                    attributes,
+                    closing: false,
+                    self_closing: true,
+                });
+            }
+        }
+        last_line_style = Some(line_style);
+    }
+    merged
+}
+
+// as SVG collapses whitespace in text anyway
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut last_space = false;
+    for c in text.chars() {
+        let space = c.is_whitespace();
+        if !(space && last_space) {
+            collapsed.push(if space { ' ' } else { c });
+        }
+        last_space = space;
+    }
+    collapsed
+}
+
+fn minify_css(css: &str) -> String {
+    let mut css = collapse_whitespace(css.trim());
+    for symbol in ["{", "}", ":", ";", ","] {
+        css = css
+            .replace(&format!(" {}", symbol), symbol)
+            .replace(&format!("{} ", symbol), symbol);
+    }
+    css.replace(";}", "}")
+}
+
+// class on the root <svg> which the palette's rules are scoped to, so
+// diagrams in different palettes can share a page, eg cg-160c1c-FBF6E2
+fn palette_class(palette: &Palette) -> String {
+    let colour =
+        |colour: &str| -> String { colour.chars().filter(char::is_ascii_alphanumeric).collect() };
+    format!("cg-{}-{}", colour(palette.bg), colour(palette.fg))
+}
+
+/// Shrinks an SVG for pages with lots of diagrams: comments and indentation
+/// go, runs of matching `<line>`s become one `<path>` and palette colours
+/// move into classes in a stylesheet. It draws the same as the input.
+pub fn minify(svg: &str, palette: &Palette) -> String {
+    let scope = palette_class(palette);
+    let mut output = String::new();
+    let mut elements: Vec<Element> = vec![];
+    let mut parent = String::new();
+    // whether the weight is still the default 400 inside each open element
+    let mut default_weight: Vec<bool> = vec![];
+    let flush = |elements: &mut Vec<Element>, output: &mut String| {
+        for mut element in merge_lines(std::mem::take(elements)) {
+            remove_defaults(&mut element);
+            let root = element.name == "svg" && !element.closing;
+            if root {
+                element.add_class(&scope);
+            } else {
+                use_classes(&mut element, palette);
+            }
+            *output += &element.write();
+            if root {
+                *output += &format!(
+                    "<style>.{scope} .f{{fill:{}}}.{scope} .s{{stroke:{}}}.{scope} .b{{fill:{}}}</style>",
+                    palette.fg,
+                    palette.fg,
+                    palette.bg,
+                    scope = scope
+                );
+            }
+        }
+    };
+
+    for token in tokenise(svg) {
+        match token {
+            Token::Tag(tag) => {
+                let mut element = Element::parse(tag);
+                let inherited = default_weight.last().copied().unwrap_or(true);
+                if inherited && element.get("font-weight") == Some("400") {
+                    element.remove("font-weight");
+                }
+                if element.closing {
+                    default_weight.pop();
+                } else if !element.self_closing {
+                    default_weight.push(inherited && !sets_weight(&element));
+                }
+                if !element.self_closing {
+                    parent = if element.closing {
+                        String::new()
+                    } else {
+                        element.name.clone()
+                    };
+                }
+                elements.push(element);
+            }
+            // whitespace between elements doesn't draw anything
+            Token::Text(text) if text.trim().is_empty() => (),
+            Token::Text(text) => {
+                flush(&mut elements, &mut output);
+                output += &match parent.as_str() {
+                    "style" => minify_css(text),
+                    "text" | "tspan" | "title" | "desc" => collapse_whitespace(text),
+                    _ => text.to_string(),
+                };
+            }
+        }
+    }
+    flush(&mut elements, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        minify::{minify, minify_css, tokenise, Element, Token},
+        utils::Palette,
+    };
+
+    const PALETTE: Palette = Palette {
+        fg: "#000",
+        bg: "#fff",
+    };
+
+    #[test]
+    fn should_tokenise() {
+        assert_eq!(
+            tokenise("<svg>\n  <!-- grid -->\n  <text x=\"1\">A</text></svg>"),
+            vec![
+                Token::Tag("<svg>"),
+                Token::Text("\n  "),
+                Token::Text("\n  "),
+                Token::Tag("<text x=\"1\">"),
+                Token::Text("A"),
+                Token::Tag("</text>"),
+                Token::Tag("</svg>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_elements() {
+        let element = Element::parse("<circle cx=\"1\"\n   fill='#000' />");
+        assert_eq!(element.name, "circle");
+        assert_eq!(element.get("cx"), Some("1"));
+        assert_eq!(element.get("fill"), Some("#000"));
+        assert!(element.self_closing);
+        assert_eq!(element.write(), "<circle cx=\"1\" fill=\"#000\"/>");
+
+        let element = Element::parse("</text>");
+        assert!(element.closing);
+        assert_eq!(element.write(), "</text>");
+    }
+
+    #[test]
+    fn should_minify_css() {
+        assert_eq!(
+            minify_css("\n    .text {\n      font-family: Seravek, 'Gill Sans Nova', sans-serif;\n    }\n  "),
+            ".text{font-family:Seravek,'Gill Sans Nova',sans-serif}"
+        );
+    }
+
+    #[test]
+    fn should_merge_lines() {
+        let svg = "<svg>
+  <line stroke-width=\"2\" stroke=\"#000\" x1=\"50\" y1=\"50\" x2=\"50\" y2=\"250\" />
+  <line stroke-width=\"2\" stroke=\"#000\" x1=\"90\" y1=\"50\" x2=\"90\" y2=\"250\" />
+  <line stroke-width=\"9\" stroke=\"#000\" x1=\"49\" y1=\"50\" x2=\"251\" y2=\"50\" />
+  <line stroke-width=\"2\" stroke=\"#000\" x1=\"50\" y1=\"90\" x2=\"250\" y2=\"90\" />
+</svg>";
+        assert_eq!(
+            minify(svg, &PALETTE),
+            "<svg class=\"cg-fff-000\"><style>.cg-fff-000 .f{fill:#000}.cg-fff-000 .s{stroke:#000}.cg-fff-000 .b{fill:#fff}</style><path stroke-width=\"2\" d=\"M50 50V250M90 50V250\" class=\"s\"/><path stroke-width=\"9\" d=\"M49 50H251\" class=\"s\"/><path stroke-width=\"2\" d=\"M50 90H250\" class=\"s\"/></svg>"
+        );
+    }
+
+    #[test]
+    fn should_use_classes() {
+        let svg = "<svg><style>.text { font-size: 12px; }</style>
+  <rect fill=\"#fff\" />
+  <text class=\"text\" fill=\"#000\" font-weight=\"400\">A  m</text>
+  <circle fill=\"transparent\" stroke=\"#000\" />
+</svg>";
+        assert_eq!(
+            minify(svg, &PALETTE),
+            "<svg class=\"cg-fff-000\"><style>.cg-fff-000 .f{fill:#000}.cg-fff-000 .s{stroke:#000}.cg-fff-000 .b{fill:#fff}</style><style>.text{font-size:12px}</style><rect class=\"b\"/><text class=\"text f\">A m</text><circle fill=\"transparent\" class=\"s\"/></svg>"
+        );
+    }
+
+    #[test]
+    fn should_keep_weights_which_differ_from_the_parent() {
+        let svg = "<svg><text font-weight=\"700\">A<tspan font-weight=\"400\">m</tspan></text><text font-weight=\"400\">7</text></svg>";
+        assert_eq!(
+            minify(svg, &PALETTE),
+            "<svg class=\"cg-fff-000\"><style>.cg-fff-000 .f{fill:#000}.cg-fff-000 .s{stroke:#000}.cg-fff-000 .b{fill:#fff}</style><text font-weight=\"700\">A<tspan font-weight=\"400\">m</tspan></text><text>7</text></svg>"
+        );
+    }
+
+    #[test]
+    fn should_scope_palettes_to_each_diagram() {
+        let svg = "<svg><rect fill=\"#fff\" /><text fill=\"#000\">A</text></svg>";
+        let dark = Palette {
+            fg: "#fff",
+            bg: "#000",
+        };
+        // both inlined in one page
+        let page = format!("{}{}", minify(svg, &PALETTE), minify(svg, &dark));
+        assert_eq!(
+            page,
+            "<svg class=\"cg-fff-000\"><style>.cg-fff-000 .f{fill:#000}.cg-fff-000 .s{stroke:#000}.cg-fff-000 .b{fill:#fff}</style><rect class=\"b\"/><text class=\"f\">A</text></svg>\
+<svg class=\"cg-000-fff\"><style>.cg-000-fff .f{fill:#fff}.cg-000-fff .s{stroke:#fff}.cg-000-fff .b{fill:#000}</style><rect class=\"f\"/><text class=\"b\">A</text></svg>"
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::ops::RangeInclusive;
+
+use tera::{Context as TeraContext, Tera};
+
+use crate::scale::{scale_notes, ScaleNote};
+use crate::svg::{
+    svg_draw_bg, svg_draw_footer, svg_draw_fret_number, svg_draw_inlay, svg_draw_line,
+    svg_draw_neck_note, svg_draw_title,
+};
+use crate::theory::string_note;
+use crate::types::{Chord, Footer, Hand, Neck};
+use crate::utils::{escape_xml, get_filename, get_palette, Palette, FOOTER_HEIGHT, WIDTH};
+
+const TEMPLATE: &str = include_str!("../templates/neck.svg");
+
+const FRET_SPACE: i32 = 50;
+const STRINGS_HEIGHT: i32 = 150; // top string to bottom string
+const NUT: i32 = 60; // open strings are drawn left of the nut
+const TOP: i32 = 50; // highest string
+const HEIGHT: i32 = 260;
+const LENGTHS: RangeInclusive<i32> = 12..=24; // frets a neck can have
+
+// frets with a dot inlay, and the octaves with two
+const INLAYS: [i32; 8] = [3, 5, 7, 9, 15, 17, 19, 21];
+const DOUBLE_INLAYS: [i32; 2] = [12, 24];
+
+// Where things go on a neck drawn sideways, with the nut on the left for
+// right-handed players
+struct Layout {
+    frets: i32,
+    strings: usize,
+    hand: Hand,
+}
+
+impl Layout {
+    fn width(&self) -> i32 {
+        NUT + self.frets * FRET_SPACE + 20
+    }
+
+    fn mirror(&self, x: i32) -> i32 {
+        match self.hand {
+            Hand::Right => x,
+            Hand::Left => self.width() - x,
+        }
+    }
+
+    fn fret_line(&self, fret: i32) -> i32 {
+        self.mirror(NUT + fret * FRET_SPACE)
+    }
+
+    // middle of the fret, or left of the nut for open strings
+    fn fret_centre(&self, fret: i32) -> i32 {
+        match fret {
+            0 => self.mirror(NUT - 25),
+            fret => self.mirror(NUT + fret * FRET_SPACE - FRET_SPACE / 2),
+        }
+    }
+
+    // strings are spread over the same height however many there are
+    fn string_space(&self) -> i32 {
+        STRINGS_HEIGHT / (self.strings.max(2) as i32 - 1)
+    }
+
+    // highest string at the top, as in tab
+    fn string(&self, string: usize) -> i32 {
+        let from_top = self.strings.saturating_sub(string + 1) as i32;
+        TOP + from_top * self.string_space()
+    }
+
+    fn bottom(&self) -> i32 {
+        self.string(0)
+    }
+
+    // halfway between a string, counted from the top, and the one below
+    fn between_strings(&self, from_top: usize) -> i32 {
+        TOP + from_top as i32 * self.string_space() + self.string_space() / 2
+    }
+}
+
+fn draw_neck(layout: &Layout, palette: &Palette) -> (String, String, String) {
+    let mut grid = String::new();
+    for string in 0..layout.strings {
+        let y = layout.string(string);
+        grid += &svg_draw_line(
+            "grid",
+            2,
+            (layout.fret_line(0), y),
+            (layout.fret_line(layout.frets), y),
+            palette,
+        );
+    }
+    for fret in 0..=layout.frets {
+        let x = layout.fret_line(fret);
+        let (class, width) = if fret == 0 { ("nut", 9) } else { ("grid", 2) };
+        grid += &svg_draw_line(class, width, (x, TOP), (x, layout.bottom()), palette);
+    }
+
+    // one inlay in the middle, or just below the middle string, and pairs
+    // two strings in from each edge
+    let middle = layout.between_strings((layout.strings.max(2) - 1) / 2);
+    let pair = (layout.strings.max(4) - 2) / 2 - 1;
+    let mut inlays = String::new();
+    let mut numbers = String::new();
+    for fret in 1..=layout.frets {
+        let x = layout.fret_centre(fret);
+        if INLAYS.contains(&fret) {
+            inlays += &svg_draw_inlay(x, middle, palette);
+        } else if DOUBLE_INLAYS.contains(&fret) {
+            inlays += &svg_draw_inlay(x, layout.between_strings(pair), palette);
+            let lower = layout.strings.saturating_sub(2 + pair);
+            inlays += &svg_draw_inlay(x, layout.between_strings(lower), palette);
+        }
+        numbers += &svg_draw_fret_number(fret, x, layout.bottom() + 25, palette);
+    }
+    (grid, inlays, numbers)
+}
+
+// strings and frets played by the shape, leaving out any past the last fret
+// or the last string
+fn shape_notes(shape: &Chord, last_fret: i32, strings: usize) -> Vec<(usize, i32)> {
+    shape
+        .frets
+        .iter()
+        .take(strings)
+        .enumerate()
+        .filter_map(|(string, fret)| fret.number().map(|fret| (string, fret)))
+        .filter(|(_, fret)| *fret <= last_fret)
+        .collect()
+}
+
+pub fn render_neck_template(neck: &Neck) -> Result<String, Box<dyn std::error::Error>> {
+    let id = get_filename(neck);
+    let scale = &neck.scale;
+    let palette = get_palette(scale.mode);
+    let frets = *scale.frets.end();
+    if !LENGTHS.contains(&frets) {
+        return Err(format!("a neck should be 12 to 24 frets long, not {}", frets).into());
+    }
+    let layout = Layout {
+        frets,
+        strings: scale.tuning.0.len(),
+        hand: scale.hand,
+    };
+    let width = layout.width();
+    let height = match scale.footer {
+        Footer::None => HEIGHT - FOOTER_HEIGHT,
+        _ => HEIGHT,
+    };
+
+    let (grid, inlays, fret_numbers) = draw_neck(&layout, &palette);
+
+    let shape = neck
+        .shape
+        .as_ref()
+        .map(|shape| shape_notes(shape, layout.frets, layout.strings));
+    let mut notes = scale_notes(scale);
+    // the shape is drawn even where it leaves the scale
+    for &(string, fret) in shape.iter().flatten() {
+        if !notes
+            .iter()
+            .any(|note| note.string == string && note.fret == fret)
+        {
+            let root = scale
+                .tuning
+                .0
+                .get(string)
+                .and_then(|open| string_note(*open, &fret.into()))
+                .is_some_and(|note| note % 12 == scale.root);
+            notes.push(ScaleNote { string, fret, root });
+        }
+    }
+
+    let mut note_markup = String::new();
+    for note in notes.iter() {
+        let faded = shape
+            .as_ref()
+            .is_some_and(|shape| !shape.contains(&(note.string, note.fret)));
+        note_markup += &svg_draw_neck_note(
+            layout.fret_centre(note.fret),
+            layout.string(note.string),
+            note.root,
+            faded,
+            &palette,
+        );
+    }
+
+    let title = svg_draw_title(
+        &Chord {
+            title: scale.title,
+            suffix: neck.shape.as_ref().and_then(|shape| shape.suffix),
+            ..Default::default()
+        },
+        &palette,
+    );
+    let label = scale
+        .title
+        .map(|title| title.to_string())
+        .unwrap_or_else(|| String::from("Neck"));
+    let description = format!(
+        "{}: {} notes across {} frets.",
+        label,
+        notes.len(),
+        layout.frets
+    );
+
+    let mut context = TeraContext::new();
+    context.insert("titleId", &format!("title-{}", id));
+    context.insert("descId", &format!("desc-{}", id));
+    context.insert("label", &escape_xml(&format!("{} neck diagram", label)));
+    context.insert("description", &escape_xml(&description));
+    context.insert("width", &width);
+    context.insert("height", &height);
+    // title and footer are laid out for chord diagrams
+    context.insert("centre", &((width - WIDTH) / 2));
+    context.insert(
+        "background",
+        &svg_draw_bg(scale.use_background, &width, &height, &palette),
+    );
+    context.insert("name", &title);
+    context.insert("grid", &grid);
+    context.insert("inlays", &inlays);
+    context.insert("notes", &note_markup);
+    context.insert("fretNumbers", &fret_numbers);
+    context.insert("footer", &svg_draw_footer(&scale.footer, &height, &palette));
+
+    Ok(Tera::one_off(TEMPLATE, &context, false)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        neck::{render_neck_template, shape_notes, Layout},
+        types::{frets_from_numbers, Chord, Hand, Neck, Scale, Tuning},
+    };
+
+    #[test]
+    fn should_lay_out_neck() {
+        let right = Layout {
+            frets: 12,
+            strings: 6,
+            hand: Hand::Right,
+        };
+        assert_eq!(right.width(), 680);
+        assert_eq!(right.fret_line(0), 60);
+        assert_eq!(right.fret_centre(0), 35);
+        assert_eq!(right.fret_centre(1), 85);
+        assert_eq!(right.string(5), 50);
+        assert_eq!(right.string(0), 200);
+
+        let left = Layout {
+            frets: 12,
+            strings: 6,
+            hand: Hand::Left,
+        };
+        assert_eq!(left.fret_line(0), 620);
+        assert_eq!(left.fret_centre(1), 595);
+        assert_eq!(left.string(0), 200);
+
+        // seven strings fit in the same height
+        let seven = Layout {
+            frets: 12,
+            strings: 7,
+            hand: Hand::Right,
+        };
+        assert_eq!(seven.string(6), 50);
+        assert_eq!(seven.string(5), 75);
+        assert_eq!(seven.string(0), 200);
+    }
+
+    #[test]
+    fn should_get_shape_notes() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 3, 2, 0, 1, 0]),
+            ..Default::default()
+        };
+        assert_eq!(
+            shape_notes(&chord, 12, 6),
+            vec![(1, 3), (2, 2), (3, 0), (4, 1), (5, 0)]
+        );
+
+        // past the end of the neck
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 15, 14, 12, 13, 12]),
+            ..Default::default()
+        };
+        assert_eq!(shape_notes(&chord, 12, 6), vec![(3, 12), (5, 12)]);
+
+        // past the last string of a bass
+        assert_eq!(shape_notes(&chord, 12, 4), vec![(3, 12)]);
+    }
+
+    #[test]
+    fn should_only_draw_12_to_24_frets() {
+        let neck = |frets: i32| Neck {
+            scale: Scale {
+                intervals: vec![0, 4, 7],
+                frets: 0..=frets,
+                ..Default::default()
+            },
+            shape: None,
+        };
+        assert!(render_neck_template(&neck(12)).is_ok());
+        assert!(render_neck_template(&neck(24)).is_ok());
+        for frets in [-3, 0, 11, 25, 200] {
+            assert_eq!(
+                render_neck_template(&neck(frets)).unwrap_err().to_string(),
+                format!("a neck should be 12 to 24 frets long, not {}", frets)
+            );
+        }
+    }
+
+    #[test]
+    fn should_draw_a_line_for_each_string() {
+        let neck = |tuning: Vec<u8>| Neck {
+            scale: Scale {
+                intervals: vec![0, 4, 7],
+                frets: 0..=12,
+                tuning: Tuning(tuning),
+                ..Default::default()
+            },
+            shape: None,
+        };
+        let strings = |svg: String| {
+            svg.matches("class=\"grid\"").count() - 12 // less the frets
+        };
+        // bass, guitar and seven string
+        assert_eq!(
+            strings(render_neck_template(&neck(vec![28, 33, 38, 43])).unwrap()),
+            4
+        );
+        assert_eq!(
+            strings(render_neck_template(&neck(vec![40, 45, 50, 55, 59, 64])).unwrap()),
+            6
+        );
+        let seven = render_neck_template(&neck(vec![35, 40, 45, 50, 55, 59, 64])).unwrap();
+        assert_eq!(strings(seven.clone()), 7);
+        // the B string is second from the top, with E on its 5th fret
+        assert!(seven.contains("cx=\"285\" cy=\"75\""));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::spelling::{parse_key, spell, MAJOR_KEY, MINOR_KEY};
+use crate::theory::parse_chord;
+
+const ROMAN: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+// degree (0 for the tonic), whether the numeral asks for a minor chord, and
+// what follows it
+fn parse_degree(numeral: &str) -> Option<(usize, Option<bool>, &str)> {
+    if let Some(digit) = numeral.chars().next().and_then(|c| c.to_digit(10)) {
+        let degree = (digit as usize)
+            .checked_sub(1)
+            .filter(|degree| *degree < 7)?;
+        return Some((degree, None, &numeral[1..]));
+    }
+
+    // longest first so "IV" isn't read as "I"
+    let mut by_length: Vec<(usize, &&str)> = ROMAN.iter().enumerate().collect();
+    by_length.sort_by_key(|(_, roman)| std::cmp::Reverse(roman.len()));
+    by_length.into_iter().find_map(|(degree, roman)| {
+        let start = numeral.get(..roman.len())?;
+        if start == *roman {
+            Some((degree, Some(false), &numeral[roman.len()..]))
+        } else if start == roman.to_lowercase() {
+            Some((degree, Some(true), &numeral[roman.len()..]))
+        } else {
+            None
+        }
+    })
+}
+
+/// Chord symbol for a Roman numeral (eg "vi", "V7", "♭VII", "ii°") or
+/// Nashville number (eg "6m", "5", "b7") in a key such as "G" or "Em".
+/// Degrees come from the major or natural minor scale of the key, and are
+/// spelled from it, so "IV" in F is "B♭".
+pub fn resolve_numeral(numeral: &str, key: &str) -> Option<String> {
+    let (key_letter, key_pitch, minor_key) = parse_key(key)?;
+
+    let numeral = numeral.trim();
+    let degree_start = numeral
+        .find(|c: char| !matches!(c, '♭' | 'b' | '♯' | '#'))
+        .unwrap_or(numeral.len());
+    let (accidentals, rest) = numeral.split_at(degree_start);
+    let offset: i32 = accidentals
+        .chars()
+        .map(|accidental| match accidental {
+            '♭' | 'b' => -1,
+            _ => 1,
+        })
+        .sum();
+
+    let (degree, minor, quality) = parse_degree(rest)?;
+    let scale = if minor_key { MINOR_KEY } else { MAJOR_KEY };
+    let pitch = (key_pitch as i32 + scale[degree] as i32 + offset).rem_euclid(12) as u8;
+    let root = spell(key_letter + degree, pitch);
+
+    let quality = match minor {
+        // lower case numerals are minor unless they say otherwise
+        Some(true) => match quality {
+            "°" | "o" => String::from("dim"),
+            "°7" | "o7" => String::from("dim7"),
+            "ø" | "ø7" => String::from("m7♭5"),
+            "maj7" => String::from("mMaj7"),
+            _ => format!("m{}", quality),
+        },
+        _ => match quality {
+            "+" => String::from("aug"),
+            _ => quality.to_string(),
+        },
+    };
+    let symbol = format!("{}{}", root, quality);
+    parse_chord(&symbol).map(|_| symbol)
+}
+
+/// Chord symbols for a progression of numerals such as "I vi IV V" in a key
+pub fn resolve_progression(progression: &str, key: &str) -> Result<Vec<String>, String> {
+    parse_key(key).ok_or_else(|| format!("unknown key `{}`", key))?;
+    progression
+        .split_whitespace()
+        .map(|numeral| {
+            resolve_numeral(numeral, key).ok_or_else(|| format!("unknown numeral `{}`", numeral))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::numerals::{resolve_numeral, resolve_progression};
+
+    #[test]
+    fn should_resolve_roman_numerals() {
+        let resolve = |numerals: &str, key: &str| resolve_progression(numerals, key).unwrap();
+        assert_eq!(resolve("I vi IV V", "G"), vec!["G", "Em", "C", "D"]);
+        assert_eq!(resolve("I IV V", "F"), vec!["F", "B♭", "C"]);
+        assert_eq!(resolve("ii7 V7 Imaj7", "B♭"), vec!["Cm7", "F7", "B♭maj7"]);
+        assert_eq!(resolve("i iv v", "Am"), vec!["Am", "Dm", "Em"]);
+        assert_eq!(resolve("i VI VII", "Am"), vec!["Am", "F", "G"]);
+        assert_eq!(resolve("♭VII iii", "D"), vec!["C", "F♯m"]);
+        assert_eq!(
+            resolve("vii° viiø7 vii°7", "C"),
+            vec!["Bdim", "Bm7♭5", "Bdim7"]
+        );
+        assert_eq!(resolve("III+", "C"), vec!["Eaug"]);
+    }
+
+    #[test]
+    fn should_resolve_nashville_numbers() {
+        let resolve = |numerals: &str, key: &str| resolve_progression(numerals, key).unwrap();
+        assert_eq!(resolve("1 6m 4 5", "E"), vec!["E", "C♯m", "A", "B"]);
+        assert_eq!(resolve("1 b7 4", "A"), vec!["A", "G", "D"]);
+        assert_eq!(resolve("2m7 57 1maj7", "C"), vec!["Dm7", "G7", "Cmaj7"]);
+    }
+
+    #[test]
+    fn should_reject_unknown_numerals() {
+        assert_eq!(resolve_numeral("VIII", "C"), None);
+        assert_eq!(resolve_numeral("8", "C"), None);
+        assert_eq!(resolve_numeral("Vsus9", "C"), None);
+        assert_eq!(
+            resolve_progression("I IV", "H"),
+            Err(String::from("unknown key `H`"))
+        );
+        assert_eq!(
+            resolve_progression("I X", "C"),
+            Err(String::from("unknown numeral `X`"))
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::ops::RangeInclusive;
+
+use crate::analysis::analyse;
+use crate::fingering::suggest_fingering;
+use crate::theory::parse_chord;
+use crate::types::{Fret, Tuning};
+use crate::voicing::{inversions, Inversion, Voicing};
+
+/// Where voicings for a progression can be found
+#[derive(PartialEq, Debug, Clone)]
+pub struct VoiceLeading {
+    pub frets: RangeInclusive<i32>, // starting at 0 allows open strings
+    pub max_grade: u8,              // hardest difficulty grade to use, 1 to 5
+    pub tuning: Tuning,
+}
+
+impl Default for VoiceLeading {
+    fn default() -> Self {
+        VoiceLeading {
+            frets: 0..=12,
+            max_grade: 3,
+            tuning: Tuning::default(),
+        }
+    }
+}
+
+// string sets to look on for chords with this many notes, low to high
+fn string_sets(notes: usize) -> Vec<(Voicing, Vec<usize>)> {
+    match notes {
+        3 => (0..4)
+            .map(|low| (Voicing::Close, vec![low, low + 1, low + 2]))
+            .collect(),
+        4 => vec![
+            (Voicing::Drop2, vec![0, 1, 2, 3]),
+            (Voicing::Drop2, vec![1, 2, 3, 4]),
+            (Voicing::Drop2, vec![2, 3, 4, 5]),
+            (Voicing::Drop3, vec![0, 2, 3, 4]),
+            (Voicing::Drop3, vec![1, 3, 4, 5]),
+        ],
+        _ => vec![],
+    }
+}
+
+// the same shape an octave up
+fn octave_up(inversion: &Inversion) -> Option<Inversion> {
+    let frets: Vec<Fret> = inversion
+        .frets
+        .iter()
+        .map(|fret| match fret.number() {
+            Some(number) => Fret::from(number + 12),
+            None => *fret,
+        })
+        .collect();
+    let fingering = suggest_fingering(&frets)?;
+    Some(Inversion {
+        inversion: inversion.inversion,
+        frets,
+        fingers: fingering.fingers,
+        barres: fingering.barres,
+    })
+}
+
+// every voicing of the chord within the limits, with how hard each is
+fn candidates(root: u8, intervals: &[u8], limits: &VoiceLeading) -> Vec<(Inversion, u32)> {
+    let mut found = vec![];
+    for (voicing, strings) in string_sets(intervals.len()) {
+        for inversion in inversions(root, intervals, voicing, &strings, &limits.tuning) {
+            let higher = octave_up(&inversion);
+            found.push(inversion);
+            found.extend(higher);
+        }
+    }
+
+    found
+        .into_iter()
+        .filter(|inversion| {
+            inversion
+                .frets
+                .iter()
+                .filter_map(|fret| fret.number())
+                .all(|fret| limits.frets.contains(&fret))
+        })
+        .filter_map(|inversion| {
+            let difficulty = analyse(&inversion.chord(None, None, None));
+            (difficulty.grade <= limits.max_grade).then_some((inversion, difficulty.score))
+        })
+        .collect()
+}
+
+// frets moved by the fingers on each string, with one for each string which
+// starts or stops being fretted
+fn movement(from: &[Fret], to: &[Fret]) -> u32 {
+    from.iter()
+        .zip(to.iter())
+        .map(|(from, to)| match (from.fretted(), to.fretted()) {
+            (Some(from), Some(to)) => from.abs_diff(to),
+            (None, None) => 0,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Voices each chord of a progression such as "Am7 D7 Gmaj7 Cmaj7" so the
+/// fingers move as little as possible from one chord to the next, then the
+/// easiest shapes. Triads are played on three neighbouring strings and four
+/// note chords as drop 2 or drop 3 voicings.
+pub fn voice_progression(
+    progression: &str,
+    limits: &VoiceLeading,
+) -> Result<Vec<Inversion>, String> {
+    let mut options: Vec<Vec<(Inversion, u32)>> = vec![];
+    for symbol in progression.split_whitespace() {
+        let (root, intervals) =
+            parse_chord(symbol).ok_or_else(|| format!("unknown chord `{}`", symbol))?;
+        let found = candidates(root, &intervals, limits);
+        if found.is_empty() {
+            return Err(format!("no voicing for `{}` within the limits", symbol));
+        }
+        options.push(found);
+    }
+
+    // cheapest (movement, difficulty) to reach each voicing of each chord,
+    // and the voicing of the chord before it on that path
+    let mut costs: Vec<Vec<((u32, u32), usize)>> = vec![];
+    for (chord, found) in options.iter().enumerate() {
+        let step = found
+            .iter()
+            .map(|(to, score)| match chord {
+                0 => ((0, *score), 0),
+                _ => options[chord - 1]
+                    .iter()
+                    .zip(costs[chord - 1].iter())
+                    .enumerate()
+                    .map(|(previous, ((from, _), ((moved, total), _)))| {
+                        (
+                            (moved + movement(&from.frets, &to.frets), total + score),
+                            previous,
+                        )
+                    })
+                    .min()
+                    .expect("every chord has a voicing"),
+            })
+            .collect();
+        costs.push(step);
+    }
+
+    let Some(last) = costs.last() else {
+        return Ok(vec![]);
+    };
+    let mut choice = last
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (cost, _))| *cost)
+        .map(|(index, _)| index)
+        .expect("every chord has a voicing");
+    let mut chosen = vec![];
+    for chord in (0..options.len()).rev() {
+        chosen.push(choice);
+        choice = costs[chord][choice].1;
+    }
+    chosen.reverse();
+
+    Ok(options
+        .into_iter()
+        .zip(chosen)
+        .map(|(mut found, choice)| found.swap_remove(choice).0)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        progression::{candidates, movement, voice_progression, VoiceLeading},
+        types::{frets_from_numbers, Fret},
+    };
+
+    #[test]
+    fn should_measure_movement() {
+        let from = frets_from_numbers(&[-1, -1, 10, 12, 12, 12]);
+        let to = frets_from_numbers(&[-1, -1, 10, 11, 10, 12]);
+        assert_eq!(movement(&from, &to), 3);
+        let to = frets_from_numbers(&[-1, 10, 10, 11, 10, -1]);
+        assert_eq!(movement(&from, &to), 5);
+    }
+
+    #[test]
+    fn should_voice_lead_progression() {
+        let voicings = voice_progression("Am7 D7 Gmaj7 Cmaj7", &VoiceLeading::default()).unwrap();
+        let frets: Vec<_> = voicings
+            .iter()
+            .map(|voicing| voicing.frets.clone())
+            .collect();
+        assert_eq!(
+            frets,
+            vec![
+                frets_from_numbers(&[3, 3, 2, 2, -1, -1]), // G C E A
+                frets_from_numbers(&[2, 3, 0, 2, -1, -1]), // F# C D A
+                frets_from_numbers(&[2, 2, 0, 0, -1, -1]), // F# B D G
+                frets_from_numbers(&[-1, 2, -1, 0, 1, 0]), // B G C E
+            ]
+        );
+
+        // further up the neck
+        let limits = VoiceLeading {
+            frets: 5..=10,
+            ..Default::default()
+        };
+        let voicings = voice_progression("Am7 D7 Gmaj7 Cmaj7", &limits).unwrap();
+        for pair in voicings.windows(2) {
+            assert!(movement(&pair[0].frets, &pair[1].frets) <= 4);
+        }
+    }
+
+    #[test]
+    fn should_only_use_open_strings_from_fret_0() {
+        let has_open = |limits: &VoiceLeading| {
+            candidates(7, &[0, 4, 7], limits)
+                .iter()
+                .any(|(inversion, _)| inversion.frets.contains(&Fret::Open))
+        };
+        assert!(has_open(&VoiceLeading::default()));
+        // open strings are as far from 5 to 10 as any other fret outside it
+        let limits = VoiceLeading {
+            frets: 5..=10,
+            ..Default::default()
+        };
+        assert!(!has_open(&limits));
+    }
+
+    #[test]
+    fn should_explain_missing_voicings() {
+        assert_eq!(
+            voice_progression("Am7 Xm7", &VoiceLeading::default()),
+            Err(String::from("unknown chord `Xm7`"))
+        );
+        // grades start at 1
+        let limits = VoiceLeading {
+            max_grade: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            voice_progression("Am7", &limits),
+            Err(String::from("no voicing for `Am7` within the limits"))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::describe::chord_label;
+use crate::render_template;
+use crate::spelling::localise_title;
+use crate::types::Chord;
+use crate::utils::{escape_xml, get_chord_height, WIDTH};
+
+/// Diagrams side by side in one SVG, eg the chords of a progression. Each is
+/// drawn with the template then nested at its place in the row.
+pub fn render_row_template(
+    template: &str,
+    chords: Vec<Chord>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let labels: Vec<String> = chords
+        .iter()
+        .map(|chord| {
+            let (title, suffix) = localise_title(chord);
+            let named = Chord {
+                title: title.as_ref().or(chord.title),
+                suffix: suffix.as_ref(),
+                ..Default::default()
+            };
+            chord_label(&named).unwrap_or_else(|| String::from("Chord"))
+        })
+        .collect();
+
+    let mut height = 0;
+    let mut diagrams = String::new();
+    for (i, chord) in chords.into_iter().enumerate() {
+        height = height.max(get_chord_height(&chord));
+        let svg = render_template(template, chord, Some(i))?;
+        diagrams += "\n  ";
+        diagrams += &svg.replacen("<svg ", &format!("<svg x=\"{}\" ", i as i32 * WIDTH), 1);
+    }
+
+    let width = labels.len() as i32 * WIDTH;
+    Ok(format!(
+        "<svg version=\"1.1\" width=\"{}px\" height=\"{}px\" viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\" role=\"group\" aria-label=\"{}\">{}\n</svg>",
+        width,
+        height,
+        width,
+        height,
+        escape_xml(&labels.join(", ")),
+        diagrams
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{row::render_row_template, types::frets_from_numbers, Chord, TEMPLATE};
+
+    #[test]
+    fn should_place_diagrams_side_by_side() {
+        let a = String::from("A");
+        let e = String::from("E");
+        let chords = vec![
+            Chord {
+                title: Some(&a),
+                frets: frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+                ..Default::default()
+            },
+            Chord {
+                title: Some(&e),
+                frets: frets_from_numbers(&[0, 2, 2, 1, 0, 0]),
+                ..Default::default()
+            },
+        ];
+        let svg = render_row_template(TEMPLATE, chords).unwrap();
+        assert!(svg.starts_with("<svg version=\"1.1\" width=\"600px\" height=\"310px\" viewBox=\"0 0 600 310\" xmlns=\"http://www.w3.org/2000/svg\" role=\"group\" aria-label=\"A, E\">"));
+        assert!(svg.contains("\n  <svg x=\"0\" version=\"1.1\" width=\"300px\""));
+        assert!(svg.contains("\n  <svg x=\"300\" version=\"1.1\" width=\"300px\""));
+        assert!(svg.ends_with("</svg>\n</svg>"));
+    }
+
+    #[test]
+    fn should_keep_ids_unique_for_repeated_chords() {
+        let a = String::from("A");
+        let d = String::from("D");
+        let chord = |title| Chord {
+            title: Some(title),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+            ..Default::default()
+        };
+        let chords = vec![
+            chord(&a),
+            Chord {
+                frets: frets_from_numbers(&[-1, -1, 0, 2, 3, 2]),
+                ..chord(&d)
+            },
+            chord(&a),
+        ];
+        let svg = render_row_template(TEMPLATE, chords).unwrap();
+        let ids: Vec<&str> = svg
+            .split(" id=\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .collect();
+        assert_eq!(ids.len(), 6);
+        for (i, id) in ids.iter().enumerate() {
+            assert!(!ids[i + 1..].contains(id), "{} is used twice", id);
+        }
+        assert!(ids[0].starts_with("title-") && ids[0].ends_with("-0"));
+        assert!(ids[4].starts_with("title-") && ids[4].ends_with("-2"));
+        assert!(svg.contains(&format!("aria-labelledby=\"{} {}\"", ids[4], ids[5])));
+    }
+}
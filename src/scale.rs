@@ -0,0 +1,266 @@
+use tera::{Context as TeraContext, Tera};
+
+use crate::svg::{
+    svg_draw_bg, svg_draw_footer, svg_draw_grid, svg_draw_min_fret, svg_draw_note,
+    svg_draw_open_note, svg_draw_root_note, svg_draw_title,
+};
+use crate::theory::{pitch_class, string_note};
+use crate::types::{Chord, Scale};
+use crate::utils::{escape_xml, get_filename, get_neck_height, get_palette, Transform, WIDTH};
+
+const TEMPLATE: &str = include_str!("../templates/scale.svg");
+
+/// Note of a scale on the neck
+#[derive(PartialEq, Debug)]
+pub struct ScaleNote {
+    pub string: usize, // 0 = low E
+    pub fret: i32,
+    pub root: bool,
+}
+
+/// Every note of the scale on each string, within its frets
+pub fn scale_notes(scale: &Scale) -> Vec<ScaleNote> {
+    let mut notes = vec![];
+    for (string, open) in scale.tuning.0.iter().enumerate() {
+        for fret in scale.frets.clone().filter(|fret| *fret >= 0) {
+            let interval = (*open as i32 + fret - scale.root as i32).rem_euclid(12) as u8;
+            if scale.intervals.contains(&interval) {
+                notes.push(ScaleNote {
+                    string,
+                    fret,
+                    root: interval == 0,
+                });
+            }
+        }
+    }
+    notes
+}
+
+/// The notes a chord plays, as a scale to show across the neck. The root is
+/// the chord's title, or its lowest note if the title isn't a note name.
+pub fn chord_tones<'a>(chord: &Chord<'a>) -> Scale<'a> {
+    let sounding: Vec<u8> = chord
+        .frets
+        .iter()
+        .zip(chord.tuning.0.iter())
+        .filter_map(|(fret, open)| string_note(*open, fret))
+        .collect();
+    let root = chord
+        .title
+        .and_then(|title| pitch_class(title))
+        .or_else(|| sounding.iter().min().map(|note| note % 12))
+        .unwrap_or(0);
+
+    let mut intervals: Vec<u8> = sounding
+        .iter()
+        .map(|note| (note % 12 + 12 - root) % 12)
+        .collect();
+    intervals.sort();
+    intervals.dedup();
+
+    Scale {
+        title: chord.title,
+        root,
+        intervals,
+        tuning: chord.tuning.clone(),
+        hand: chord.hand,
+        mode: chord.mode,
+        use_background: chord.use_background,
+        footer: chord.footer.clone(),
+        ..Default::default()
+    }
+}
+
+// first fret drawn, with the fret above it as on chord diagrams, and how
+// many frets that takes
+fn get_neck(scale: &Scale) -> (i32, i32) {
+    let first = *scale.frets.start();
+    let last = *scale.frets.end();
+    if first <= 2 {
+        (0, last.max(1))
+    } else {
+        (first, last - first + 2)
+    }
+}
+
+pub fn render_scale_template(scale: &Scale) -> Result<String, Box<dyn std::error::Error>> {
+    if *scale.frets.start() < 0 || scale.frets.start() > scale.frets.end() {
+        return Err(format!(
+            "a scale's frets should run up from 0, not {} to {}",
+            scale.frets.start(),
+            scale.frets.end()
+        )
+        .into());
+    }
+    let id = get_filename(scale);
+    let string_space = 40;
+    let palette = get_palette(scale.mode);
+    let transform = Transform::new(scale.hand);
+
+    let (min_fret, frets) = get_neck(scale);
+    let height = get_neck_height(frets, &string_space, &scale.footer);
+
+    let mut open_notes = String::new();
+    let mut notes = String::new();
+    let all_notes = scale_notes(scale);
+    for note in all_notes.iter() {
+        let string = transform.string(note.string);
+        if note.fret == 0 {
+            open_notes += &svg_draw_open_note(string, &string_space, note.root, &palette);
+        } else if note.root {
+            notes += &svg_draw_root_note(&note.fret, string, &string_space, &min_fret, &palette);
+        } else {
+            notes += &svg_draw_note(
+                &note.fret,
+                string,
+                &string_space,
+                &min_fret,
+                false,
+                &palette,
+            );
+        }
+    }
+
+    let min_fret_marker = if min_fret > 0 {
+        svg_draw_min_fret(&min_fret, &string_space, &palette)
+    } else {
+        String::new()
+    };
+
+    let title = svg_draw_title(
+        &Chord {
+            title: scale.title,
+            ..Default::default()
+        },
+        &palette,
+    );
+    let label = scale
+        .title
+        .map(|title| title.to_string())
+        .unwrap_or_else(|| String::from("Scale"));
+    let description = format!(
+        "{}: {} notes between frets {} and {}.",
+        label,
+        all_notes.len(),
+        scale.frets.start(),
+        scale.frets.end()
+    );
+
+    let mut context = TeraContext::new();
+    context.insert("titleId", &format!("title-{}", id));
+    context.insert("descId", &format!("desc-{}", id));
+    let aria_label = match scale.title {
+        Some(title) => format!("{} scale diagram", title),
+        None => String::from("Scale diagram"),
+    };
+    context.insert("label", &escape_xml(&aria_label));
+    context.insert("description", &escape_xml(&description));
+    context.insert("height", &height);
+    context.insert(
+        "background",
+        &svg_draw_bg(scale.use_background, &WIDTH, &height, &palette),
+    );
+    context.insert("name", &title);
+    context.insert(
+        "grid",
+        &svg_draw_grid(frets, &string_space, min_fret == 0, &palette),
+    );
+    context.insert("openNotes", &open_notes);
+    context.insert("notes", &notes);
+    context.insert("minFret", &min_fret_marker);
+    context.insert("footer", &svg_draw_footer(&scale.footer, &height, &palette));
+
+    Ok(Tera::one_off(TEMPLATE, &context, false)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        scale::{chord_tones, get_neck, scale_notes, ScaleNote},
+        types::{frets_from_numbers, position, Chord, Scale},
+    };
+
+    #[test]
+    fn should_find_scale_notes() {
+        // A minor pentatonic, 5th position
+        let scale = Scale {
+            root: 9,
+            intervals: vec![0, 3, 5, 7, 10],
+            frets: position(5),
+            ..Default::default()
+        };
+        let notes = scale_notes(&scale);
+        let low_e: Vec<i32> = notes
+            .iter()
+            .filter(|note| note.string == 0)
+            .map(|note| note.fret)
+            .collect();
+        assert_eq!(low_e, vec![5, 8]);
+        assert_eq!(notes.len(), 13);
+        assert_eq!(
+            notes.iter().filter(|note| note.root).count(),
+            3 // low E, D and high E strings
+        );
+        assert_eq!(
+            notes[0],
+            ScaleNote {
+                string: 0,
+                fret: 5,
+                root: true
+            }
+        );
+    }
+
+    #[test]
+    fn should_include_open_strings() {
+        // E minor arpeggio
+        let scale = Scale {
+            root: 4,
+            intervals: vec![0, 3, 7],
+            frets: 0..=2,
+            ..Default::default()
+        };
+        let open: Vec<usize> = scale_notes(&scale)
+            .iter()
+            .filter(|note| note.fret == 0)
+            .map(|note| note.string)
+            .collect();
+        // E, G, B and E ring open
+        assert_eq!(open, vec![0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn should_size_neck() {
+        let scale = |frets| Scale {
+            frets,
+            ..Default::default()
+        };
+        assert_eq!(get_neck(&scale(position(0))), (0, 4));
+        assert_eq!(get_neck(&scale(0..=12)), (0, 12));
+        assert_eq!(get_neck(&scale(position(2))), (0, 6));
+        assert_eq!(get_neck(&scale(position(5))), (5, 6));
+        assert_eq!(get_neck(&scale(0..=0)), (0, 1));
+    }
+
+    #[test]
+    fn should_get_chord_tones() {
+        let title = String::from("A");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 2, 0]),
+            ..Default::default()
+        };
+        let tones = chord_tones(&chord);
+        assert_eq!(tones.root, 9);
+        assert_eq!(tones.intervals, vec![0, 4, 7]);
+
+        // root from the lowest note without a title
+        let chord = Chord {
+            frets: frets_from_numbers(&[3, 2, 0, 0, 3, 3]),
+            ..Default::default()
+        };
+        let tones = chord_tones(&chord);
+        assert_eq!(tones.root, 7);
+        assert_eq!(tones.intervals, vec![0, 4, 7]);
+    }
+}
@@ -0,0 +1,775 @@
+use crate::numerals::resolve_numeral;
+use crate::theory::{
+    chord_degrees, parse_chord, pitch_class, split_note, string_note, title_root, CHORD_QUALITIES,
+};
+use crate::types::{Accidentals, Chord, Fret, NoteNaming, Tuning};
+
+// pitch classes of the natural notes, C first
+const NATURALS: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+pub(crate) const LETTERS: [char; 7] = ['C', 'D', 'E', 'F', 'G', 'A', 'B'];
+
+pub(crate) const MAJOR_KEY: [u8; 7] = [0, 2, 4, 5, 7, 9, 11];
+pub(crate) const MINOR_KEY: [u8; 7] = [0, 2, 3, 5, 7, 8, 10];
+
+// the usual names of keys and lone notes on each pitch class, C first
+const MAJOR_NAMES: [&str; 12] = [
+    "C", "D♭", "D", "E♭", "E", "F", "F♯", "G", "A♭", "A", "B♭", "B",
+];
+const MINOR_NAMES: [&str; 12] = [
+    "C", "C♯", "D", "E♭", "E", "F", "F♯", "G", "G♯", "A", "B♭", "B",
+];
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C♯", "D", "E♭", "E", "F", "F♯", "G", "A♭", "A", "B♭", "B",
+];
+
+const SOLFEGE: [&str; 7] = ["Do", "Re", "Mi", "Fa", "Sol", "La", "Si"];
+const NASHVILLE: [&str; 12] = [
+    "1", "♭2", "2", "♭3", "3", "4", "♯4", "5", "♭6", "6", "♭7", "7",
+];
+
+/// Name of the note with this pitch class written on this letter (C = 0), eg
+/// pitch class 10 on B is "B♭" and on A is "A♯"
+pub fn spell(letter: usize, pitch: u8) -> String {
+    let letter = letter % 7;
+    let accidental = match (pitch as i32 - NATURALS[letter] as i32).rem_euclid(12) {
+        0 => "",
+        1 => "♯",
+        2 => "♯♯",
+        10 => "♭♭",
+        11 => "♭",
+        _ => return format!("{}?", LETTERS[letter]),
+    };
+    format!("{}{}", LETTERS[letter], accidental)
+}
+
+// letter a note name is written on
+fn letter(name: &str) -> Option<usize> {
+    let first = name.trim().chars().next()?.to_ascii_uppercase();
+    LETTERS.iter().position(|letter| *letter == first)
+}
+
+// letter, pitch class and whether a key such as "G" or "F#m" is minor
+pub(crate) fn parse_key(key: &str) -> Option<(usize, u8, bool)> {
+    let (note, quality) = split_note(key);
+    let minor = match quality {
+        "" | "maj" | "major" => false,
+        "m" | "min" | "minor" => true,
+        _ => return None,
+    };
+    Some((letter(note)?, pitch_class(note)?, minor))
+}
+
+// the sharp or flat name of a pitch class
+fn forced_name(pitch: u8, flats: bool) -> String {
+    let pitch = pitch % 12;
+    match NATURALS.iter().position(|natural| *natural == pitch) {
+        Some(letter) => spell(letter, pitch),
+        None if flats => spell(
+            NATURALS.iter().position(|n| *n == pitch + 1).unwrap_or(0),
+            pitch,
+        ),
+        None => spell(
+            NATURALS.iter().position(|n| *n + 1 == pitch).unwrap_or(0),
+            pitch,
+        ),
+    }
+}
+
+/// Name of a pitch class without a key, eg "B♭", or "A♯" with sharps
+pub fn note_name(pitch: u8, accidentals: Accidentals) -> String {
+    match accidentals {
+        Accidentals::Auto => NOTE_NAMES[pitch as usize % 12].to_string(),
+        Accidentals::Sharps => forced_name(pitch, false),
+        Accidentals::Flats => forced_name(pitch, true),
+    }
+}
+
+/// Name of a pitch class in a key such as "F" or "C#m". Notes of the key are
+/// spelled with one letter each, so E♭ in B♭ and D♯ in E, as are the raised
+/// 6th and 7th of a minor key, so C♯ in D minor. The others take the
+/// accidentals of its key signature.
+pub fn name_in_key(pitch: u8, key: &str, accidentals: Accidentals) -> Option<String> {
+    let (key_letter, key_pitch, minor) = parse_key(key)?;
+    if accidentals != Accidentals::Auto {
+        return Some(note_name(pitch, accidentals));
+    }
+
+    let scale = if minor { MINOR_KEY } else { MAJOR_KEY };
+    let names: Vec<String> = scale
+        .iter()
+        .enumerate()
+        .map(|(degree, interval)| spell(key_letter + degree, (key_pitch + interval) % 12))
+        .collect();
+    let interval = (pitch as i32 - key_pitch as i32).rem_euclid(12) as u8;
+    if let Some(degree) = scale.iter().position(|note| *note == interval) {
+        return Some(names[degree].clone());
+    }
+    // harmonic and melodic minor
+    match (minor, interval) {
+        (true, 9) => return Some(spell(key_letter + 5, pitch % 12)),
+        (true, 11) => return Some(spell(key_letter + 6, pitch % 12)),
+        _ => {}
+    }
+    let flats = names.iter().any(|name| name.contains('♭'));
+    Some(forced_name(pitch, flats))
+}
+
+/// Name of a pitch class in the chord with this root note, eg "C" and "m7".
+/// Chord tones are spelled from the root, so the third of A♭ is C rather
+/// than B♯, and the fifth of Caug is G♯.
+pub fn name_in_chord(
+    pitch: u8,
+    root: &str,
+    quality: &str,
+    accidentals: Accidentals,
+) -> Option<String> {
+    let root_letter = letter(root)?;
+    let root_pitch = pitch_class(root)?;
+    if accidentals != Accidentals::Auto {
+        return Some(note_name(pitch, accidentals));
+    }
+
+    let interval = (pitch as i32 - root_pitch as i32).rem_euclid(12) as u8;
+    let degree = chord_degrees(quality)
+        .and_then(|degrees| {
+            degrees
+                .into_iter()
+                .find(|(_, semitones)| *semitones == interval)
+        })
+        .map(|(letters, _)| letters);
+    // notes outside the chord as ♭2, ♭3, ♯4, ♭6 and ♭7 of the root
+    let letters = degree.unwrap_or(match interval {
+        0 => 0,
+        1 | 2 => 1,
+        3 | 4 => 2,
+        5 | 6 => 3,
+        7 => 4,
+        8 | 9 => 5,
+        _ => 6,
+    });
+
+    let name = spell(root_letter + letters, pitch % 12);
+    // double sharps and flats only when the chord needs them, as in G♯aug
+    if (name.contains("♯♯") || name.contains("♭♭")) && degree.is_none() {
+        Some(forced_name(pitch, root.contains(['♭', 'b'])))
+    } else {
+        Some(name)
+    }
+}
+
+// MIDI note sounding on each string of a chord, low E first
+fn chord_notes(chord: &Chord) -> Vec<Option<u8>> {
+    chord
+        .frets
+        .iter()
+        .enumerate()
+        .map(|(i, fret)| string_note(*chord.tuning.0.get(i)?, fret))
+        .collect()
+}
+
+/// Name of a pitch class on a chord diagram, spelled from its title when it
+/// is a chord, and written in its naming system
+pub(crate) fn chord_note_name(chord: &Chord, pitch: u8) -> String {
+    let full_title = chord.title.map_or("", |title| title.as_str());
+    let (title, rest) = split_note(full_title);
+    let quality = chord.suffix.map_or(rest, |suffix| suffix.as_str());
+    let name = title_root(full_title)
+        .and_then(|_| name_in_chord(pitch, title, quality, chord.accidentals))
+        .unwrap_or_else(|| note_name(pitch, chord.accidentals));
+    localise(&name, chord.naming)
+}
+
+// names of intervals above the root, unless the chord says otherwise
+const INTERVALS: [&str; 12] = [
+    "R", "♭2", "2", "♭3", "3", "4", "♭5", "5", "♭6", "6", "♭7", "7",
+];
+
+/// Note and interval above the root sounding on each string, low E first,
+/// eg ("C", "♭3"), or None for strings which aren't played. The root is the
+/// title's note, or the lowest note if the title isn't one. Chord tones are
+/// named from the chord, so the fifth of Caug is ♯5 rather than ♭6.
+pub fn chord_spelling(chord: &Chord) -> Vec<Option<(String, String)>> {
+    let notes = chord_notes(chord);
+    let full_title = chord.title.map_or("", |title| title.as_str());
+    let (title, rest) = split_note(full_title);
+    let quality = chord.suffix.map_or(rest, |suffix| suffix.as_str());
+    // notes are spelled from the lowest if the title isn't a chord
+    let root_name = match title_root(full_title) {
+        Some(_) => title.to_string(),
+        None => match notes.iter().flatten().min() {
+            Some(lowest) => note_name(lowest % 12, chord.accidentals),
+            None => return vec![None; notes.len()],
+        },
+    };
+    let root = pitch_class(&root_name).unwrap_or(0);
+    let degrees = chord_degrees(quality).unwrap_or_default();
+
+    notes
+        .iter()
+        .map(|note| {
+            let pitch = (*note)? % 12;
+            let interval = (pitch + 12 - root) % 12;
+            let name = match degrees.iter().find(|(_, semitones)| *semitones == interval) {
+                Some((0, _)) | None => INTERVALS[interval as usize].to_string(),
+                Some((letters, semitones)) => {
+                    let offset = *semitones as i32 - MAJOR_KEY[*letters] as i32;
+                    let accidentals = if offset < 0 { "♭" } else { "♯" };
+                    format!(
+                        "{}{}",
+                        accidentals.repeat(offset.unsigned_abs() as usize),
+                        letters + 1
+                    )
+                }
+            };
+            let note = name_in_chord(pitch, &root_name, quality, chord.accidentals)
+                .unwrap_or_else(|| note_name(pitch, chord.accidentals));
+            Some((localise(&note, chord.naming), name))
+        })
+        .collect()
+}
+
+// diminished and augmented chords lead up a semitone, so are written with
+// sharps, eg G♯dim to A
+fn leads_up(quality: &str) -> bool {
+    matches!(
+        quality,
+        "dim" | "o" | "°" | "dim7" | "o7" | "°7" | "m7b5" | "m7♭5" | "ø" | "ø7" | "aug" | "+"
+    )
+}
+
+/// Renames the root with its usual name as a key, eg "A♯m" as "B♭m", or
+/// with sharps for diminished and augmented chords, or with the sharps or
+/// flats asked for
+fn respell_root(root: u8, quality: &str, accidentals: Accidentals) -> String {
+    match accidentals {
+        Accidentals::Auto if leads_up(quality) => forced_name(root, false),
+        Accidentals::Auto => {
+            let minor = quality.starts_with('m') && !quality.starts_with("maj");
+            let names = if minor { MINOR_NAMES } else { MAJOR_NAMES };
+            names[root as usize % 12].to_string()
+        }
+        _ => note_name(root, accidentals),
+    }
+}
+
+/// Moves a chord symbol such as "B♭m7" or "D/F#" up (or down, if negative)
+/// by some semitones and spells the new name, so "A#" up 0 is "B♭". Anything
+/// after the note is kept, so free text titles can be transposed too.
+pub fn transpose(symbol: &str, semitones: i32, accidentals: Accidentals) -> Option<String> {
+    let (chord, bass) = match symbol.trim().split_once('/') {
+        Some((chord, bass)) => (chord, Some(bass)),
+        None => (symbol.trim(), None),
+    };
+    let (note, quality) = split_note(chord);
+    let move_note =
+        |note: &str| pitch_class(note).map(|pitch| (pitch as i32 + semitones).rem_euclid(12) as u8);
+    let root = respell_root(move_note(note)?, quality, accidentals);
+
+    match bass {
+        Some(bass) => {
+            let (bass_note, rest) = split_note(bass);
+            let bass = name_in_chord(move_note(bass_note)?, &root, quality, accidentals)?;
+            Some(format!("{}{}/{}{}", root, quality, bass, rest))
+        }
+        None => Some(format!("{}{}", root, quality)),
+    }
+}
+
+/// Names the chord played by these frets, eg "Am7" or "C/E", or None if it
+/// isn't a chord the theory module knows. A root on the lowest string is
+/// preferred, otherwise the lowest note is written as a slash bass.
+pub fn name_chord(frets: &[Fret], tuning: &Tuning, accidentals: Accidentals) -> Option<String> {
+    let notes: Vec<u8> = frets
+        .iter()
+        .zip(tuning.0.iter())
+        .filter_map(|(fret, open)| string_note(*open, fret))
+        .collect();
+    let bass = *notes.iter().min()? % 12;
+    let mut pitches: Vec<u8> = notes.iter().map(|note| note % 12).collect();
+    pitches.sort();
+    pitches.dedup();
+
+    let mut roots = vec![bass];
+    roots.extend(pitches.iter().filter(|pitch| **pitch != bass));
+    for root in roots {
+        for quality in CHORD_QUALITIES {
+            let Some(degrees) = chord_degrees(quality) else {
+                continue;
+            };
+            let mut intervals: Vec<u8> = degrees.iter().map(|(_, semitones)| *semitones).collect();
+            intervals.sort();
+            let mut played: Vec<u8> = pitches
+                .iter()
+                .map(|pitch| (*pitch as i32 - root as i32).rem_euclid(12) as u8)
+                .collect();
+            played.sort();
+            if intervals != played {
+                continue;
+            }
+
+            let name = respell_root(root, quality, accidentals);
+            if root == bass {
+                return Some(format!("{}{}", name, quality));
+            }
+            let bass_name = name_in_chord(bass, &name, quality, accidentals)?;
+            return Some(format!("{}{}/{}", name, quality, bass_name));
+        }
+    }
+    None
+}
+
+// H for B and B for B♭, sharps with -is and flats with -es, eg Fis and Es
+fn german(letter: usize, sharps: usize, flats: usize) -> String {
+    match (LETTERS[letter], sharps, flats) {
+        ('B', 0, 1) => String::from("B"),
+        ('B', sharps, flats) => format!("H{}{}", "is".repeat(sharps), "es".repeat(flats)),
+        (name @ ('E' | 'A'), 0, flats) if flats > 0 => {
+            format!("{}s{}", name, "es".repeat(flats - 1))
+        }
+        (name, sharps, flats) => format!("{}{}{}", name, "is".repeat(sharps), "es".repeat(flats)),
+    }
+}
+
+/// Writes an English note name such as "B♭" in another naming system: "B" in
+/// German, "Si♭" in solfège, or "4" with Nashville numbers in F
+pub fn localise(name: &str, naming: NoteNaming) -> String {
+    let name = name.trim();
+    let Some(letter) = letter(name) else {
+        return name.to_string();
+    };
+    let accidentals = &name[1..];
+    let sharps = accidentals.matches(['♯', '#']).count();
+    let flats = accidentals.matches(['♭', 'b']).count();
+    match naming {
+        NoteNaming::English => name.to_string(),
+        NoteNaming::German => german(letter, sharps, flats),
+        NoteNaming::Solfege => format!(
+            "{}{}{}",
+            SOLFEGE[letter],
+            "♯".repeat(sharps),
+            "♭".repeat(flats)
+        ),
+        NoteNaming::Nashville(tonic) => match pitch_class(name) {
+            Some(pitch) => NASHVILLE[(pitch as usize + 12 - tonic as usize % 12) % 12].to_string(),
+            None => name.to_string(),
+        },
+    }
+}
+
+// a chord symbol or a bare note name
+fn is_chord(symbol: &str) -> bool {
+    let (note, quality) = split_note(symbol);
+    pitch_class(note).is_some() && (quality.is_empty() || parse_chord(symbol).is_some())
+}
+
+/// Writes the note of a chord symbol such as "Fism7" or "C/E" in another
+/// naming system. Anything which isn't a chord, like "Hendrix", is None.
+pub fn localise_symbol(symbol: &str, naming: NoteNaming) -> Option<String> {
+    let (chord, bass) = match symbol.trim().split_once('/') {
+        Some((chord, bass)) => (chord, Some(bass)),
+        None => (symbol.trim(), None),
+    };
+    if !is_chord(chord) {
+        return None;
+    }
+    let (note, quality) = split_note(chord);
+    let chord = format!("{}{}", localise(note, naming), quality);
+    match bass {
+        Some(bass) if pitch_class(bass).is_some() && split_note(bass).1.is_empty() => {
+            Some(format!("{}/{}", chord, localise(bass, naming)))
+        }
+        Some(_) => None,
+        None => Some(chord),
+    }
+}
+
+/// Title and suffix of a chord written in its naming system. Titles which
+/// aren't chords are None, and kept as they are.
+pub(crate) fn localise_title(chord: &Chord) -> (Option<String>, Option<String>) {
+    (
+        chord
+            .title
+            .and_then(|title| localise_symbol(title, chord.naming)),
+        chord
+            .suffix
+            .map(|suffix| localise_suffix(suffix, chord.naming)),
+    )
+}
+
+/// Open note of each string, low E first, written in the chord's naming
+/// system, eg "E A D G H E" in German. Tunings with any flat are written
+/// all in flats, so E♭ tuning has a D♭ rather than a C♯.
+pub(crate) fn tuning_names(chord: &Chord) -> Vec<String> {
+    let opens = chord.tuning.0.iter().map(|open| open % 12);
+    let accidentals = match chord.accidentals {
+        Accidentals::Auto
+            if opens
+                .clone()
+                .any(|open| NOTE_NAMES[open as usize].contains('♭')) =>
+        {
+            Accidentals::Flats
+        }
+        accidentals => accidentals,
+    };
+    opens
+        .map(|open| localise(&note_name(open, accidentals), chord.naming))
+        .collect()
+}
+
+// writes the bass note of a suffix such as "m7/G" in another naming system
+fn localise_suffix(suffix: &str, naming: NoteNaming) -> String {
+    match suffix.split_once('/') {
+        Some((quality, bass)) if pitch_class(bass).is_some() && split_note(bass).1.is_empty() => {
+            format!("{}/{}", quality, localise(bass, naming))
+        }
+        _ => suffix.to_string(),
+    }
+}
+
+// English readings of a chord symbol written in a naming system, longest
+// note names first so "Fis" isn't read as "F"
+fn readings(symbol: &str, naming: NoteNaming) -> Vec<String> {
+    let mut names: Vec<(String, String)> = vec![];
+    for letter in 0..LETTERS.len() {
+        for (sharps, flats) in [(0, 0), (1, 0), (2, 0), (0, 1), (0, 2)] {
+            let english = format!(
+                "{}{}{}",
+                LETTERS[letter],
+                "♯".repeat(sharps),
+                "♭".repeat(flats)
+            );
+            match naming {
+                NoteNaming::German => names.push((german(letter, sharps, flats), english)),
+                // accidentals after the syllable are read as English ones
+                NoteNaming::Solfege if sharps + flats == 0 => {
+                    if letter == 1 {
+                        names.push((String::from("Ré"), english.clone()));
+                    }
+                    names.push((SOLFEGE[letter].to_string(), english));
+                }
+                _ => (),
+            }
+        }
+    }
+    names.sort_by_key(|(written, _)| std::cmp::Reverse(written.chars().count()));
+
+    let mut found: Vec<String> = names
+        .into_iter()
+        .filter_map(|(written, english)| {
+            let start = symbol.get(..written.len())?;
+            let rest = &symbol[written.len()..];
+            // German names have their accidentals, so "Bb" is English
+            let accidental = rest.starts_with(['♭', 'b', '♯', '#']);
+            (start.eq_ignore_ascii_case(&written) && !(naming == NoteNaming::German && accidental))
+                .then(|| format!("{}{}", english, rest))
+        })
+        .collect();
+    if let NoteNaming::Nashville(tonic) = naming {
+        found.extend(resolve_numeral(symbol, MAJOR_NAMES[tonic as usize % 12]));
+    }
+    found.push(symbol.to_string());
+    found
+}
+
+/// Reads a chord symbol or note written in a naming system, eg "Fism7" in
+/// German or "Sol7" in solfège, as the English names used everywhere else.
+/// English names are read in any system, except B which is B♭ in German.
+pub fn to_english(symbol: &str, naming: NoteNaming) -> Option<String> {
+    let (chord, bass) = match symbol.trim().split_once('/') {
+        Some((chord, bass)) => (chord, Some(bass)),
+        None => (symbol.trim(), None),
+    };
+    let chord = readings(chord, naming)
+        .into_iter()
+        .find(|reading| is_chord(reading))?;
+    match bass {
+        Some(bass) => {
+            let bass = readings(bass, naming).into_iter().find(|reading| {
+                pitch_class(reading).is_some() && split_note(reading).1.is_empty()
+            })?;
+            Some(format!("{}/{}", chord, bass))
+        }
+        None => Some(chord),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        spelling::{
+            chord_note_name, chord_spelling, localise, localise_symbol, name_chord, name_in_chord,
+            name_in_key, note_name, spell, to_english, transpose, tuning_names,
+        },
+        types::{frets_from_numbers, Accidentals, Chord, NoteNaming, Tuning},
+    };
+
+    #[test]
+    fn should_spell_notes() {
+        assert_eq!(spell(6, 10), "B♭");
+        assert_eq!(spell(5, 10), "A♯");
+        assert_eq!(spell(3, 6), "F♯");
+        assert_eq!(spell(0, 11), "C♭");
+        assert_eq!(spell(2, 6), "E♯♯");
+    }
+
+    #[test]
+    fn should_name_notes() {
+        assert_eq!(note_name(10, Accidentals::Auto), "B♭");
+        assert_eq!(note_name(1, Accidentals::Auto), "C♯");
+        assert_eq!(note_name(10, Accidentals::Sharps), "A♯");
+        assert_eq!(note_name(6, Accidentals::Flats), "G♭");
+        assert_eq!(note_name(4, Accidentals::Flats), "E");
+    }
+
+    #[test]
+    fn should_name_notes_in_keys() {
+        let name = |pitch: u8, key: &str| name_in_key(pitch, key, Accidentals::Auto).unwrap();
+        assert_eq!(name(3, "B♭"), "E♭");
+        assert_eq!(name(3, "E"), "D♯");
+        assert_eq!(name(11, "G♭"), "C♭");
+        assert_eq!(name(5, "C♯"), "E♯");
+        // raised 6th and 7th of minor keys
+        assert_eq!(name(8, "Am"), "G♯");
+        assert_eq!(name(1, "Dm"), "C♯");
+        assert_eq!(name(6, "Gm"), "F♯");
+        assert_eq!(name(4, "Gm"), "E");
+        assert_eq!(name(11, "Cm"), "B");
+        assert_eq!(name(9, "Cm"), "A");
+        assert_eq!(name(1, "Cm"), "D♭");
+        assert_eq!(name(1, "F"), "D♭");
+        assert_eq!(name(6, "C"), "F♯");
+        assert_eq!(name(3, "Cm"), "E♭");
+        assert_eq!(
+            name_in_key(3, "E", Accidentals::Flats),
+            Some(String::from("E♭"))
+        );
+        assert_eq!(name_in_key(3, "H", Accidentals::Auto), None);
+    }
+
+    #[test]
+    fn should_name_notes_in_chords() {
+        let name = |pitch: u8, root: &str, quality: &str| {
+            name_in_chord(pitch, root, quality, Accidentals::Auto).unwrap()
+        };
+        assert_eq!(name(0, "A♭", ""), "C");
+        assert_eq!(name(3, "A♭", ""), "E♭");
+        assert_eq!(name(8, "C", "aug"), "G♯");
+        assert_eq!(name(8, "C", "m"), "A♭"); // ♭6 as it isn't in the chord
+        assert_eq!(name(6, "C", "m7♭5"), "G♭");
+        assert_eq!(name(9, "C", "dim7"), "A"); // written as a 6th in the formula
+        assert_eq!(name(4, "G♯", "aug"), "D♯♯");
+        assert_eq!(name(8, "E", ""), "G♯");
+        assert_eq!(
+            name_in_chord(8, "E", "", Accidentals::Flats),
+            Some(String::from("A♭"))
+        );
+    }
+
+    #[test]
+    fn should_transpose_chords() {
+        let up =
+            |symbol: &str, semitones: i32| transpose(symbol, semitones, Accidentals::Auto).unwrap();
+        assert_eq!(up("A#", 0), "B♭");
+        assert_eq!(up("Bbm7", 2), "Cm7");
+        assert_eq!(up("G", 4), "B");
+        assert_eq!(up("Am", 1), "B♭m");
+        assert_eq!(up("Em", -3), "C♯m");
+        assert_eq!(up("E", 2), "F♯");
+        assert_eq!(up("D/F#", 3), "F/A");
+        assert_eq!(up("C/E", 1), "D♭/F");
+        // diminished and augmented roots are sharp
+        assert_eq!(up("G#dim", 0), "G♯dim");
+        assert_eq!(up("Abdim7", 0), "G♯dim7");
+        assert_eq!(up("Fø", 1), "F♯ø");
+        assert_eq!(up("Caug", 1), "C♯aug");
+        assert_eq!(up("Am7b5", 1), "A♯m7b5");
+        assert_eq!(
+            transpose("Bb7", 0, Accidentals::Sharps),
+            Some(String::from("A♯7"))
+        );
+        assert_eq!(transpose("Hendrix", 2, Accidentals::Auto), None);
+    }
+
+    #[test]
+    fn should_name_chords_from_frets() {
+        let name = |frets: &[i32]| {
+            name_chord(
+                &frets_from_numbers(frets),
+                &Tuning::default(),
+                Accidentals::Auto,
+            )
+        };
+        assert_eq!(name(&[-1, 0, 2, 2, 1, 0]), Some(String::from("Am")));
+        assert_eq!(name(&[-1, 3, 2, 0, 1, 0]), Some(String::from("C")));
+        assert_eq!(name(&[-1, 0, 2, 0, 1, 0]), Some(String::from("Am7")));
+        assert_eq!(name(&[0, 3, 2, 0, 1, 0]), Some(String::from("C/E")));
+        assert_eq!(name(&[6, 8, 8, 7, 6, 6]), Some(String::from("B♭")));
+        assert_eq!(name(&[-1, 1, 3, 3, 3, 1]), Some(String::from("B♭")));
+        assert_eq!(name(&[4, -1, 3, 4, 3, -1]), Some(String::from("G♯dim7")));
+        assert_eq!(name(&[-1, -1, -1, -1, -1, -1]), None);
+        assert_eq!(
+            name_chord(
+                &frets_from_numbers(&[6, 8, 8, 7, 6, 6]),
+                &Tuning::default(),
+                Accidentals::Sharps
+            ),
+            Some(String::from("A♯"))
+        );
+    }
+
+    #[test]
+    fn should_spell_chords_by_string() {
+        let spelled = |chord: &Chord| -> Vec<String> {
+            chord_spelling(chord)
+                .into_iter()
+                .map(|entry| match entry {
+                    Some((note, interval)) => format!("{} {}", note, interval),
+                    None => String::from("-"),
+                })
+                .collect()
+        };
+        let title = String::from("A");
+        let suffix = String::from("m");
+        let chord = Chord {
+            title: Some(&title),
+            suffix: Some(&suffix),
+            frets: frets_from_numbers(&[-1, 0, 2, 2, 1, 0]),
+            ..Default::default()
+        };
+        assert_eq!(
+            spelled(&chord),
+            vec!["-", "A R", "E 5", "A R", "C ♭3", "E 5"]
+        );
+
+        // the fifth of an augmented chord is sharp
+        let title = String::from("Caug");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 3, 2, 1, 1, 0]),
+            ..Default::default()
+        };
+        assert_eq!(
+            spelled(&chord),
+            vec!["-", "C R", "E 3", "G♯ ♯5", "C R", "E 3"]
+        );
+
+        // from the lowest note when the title isn't a chord
+        let title = String::from("Hendrix");
+        let chord = Chord {
+            title: Some(&title),
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
+            ..Default::default()
+        };
+        assert_eq!(
+            spelled(&chord),
+            vec!["-", "E R", "G♯ 3", "D ♭7", "G ♭3", "-"]
+        );
+        // dot labels don't guess a root, so use the usual name
+        assert_eq!(chord_note_name(&chord, 8), "A♭");
+
+        // titles starting with a note letter aren't chords either
+        for title in ["Chord", "Blues shape"] {
+            let title = String::from(title);
+            let chord = Chord {
+                title: Some(&title),
+                frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
+                ..Default::default()
+            };
+            assert_eq!(
+                spelled(&chord),
+                vec!["-", "E R", "G♯ 3", "D ♭7", "G ♭3", "-"]
+            );
+            assert_eq!(chord_note_name(&chord, 3), "E♭");
+            assert_eq!(chord_note_name(&chord, 8), "A♭");
+            assert_eq!(chord_note_name(&chord, 10), "B♭");
+        }
+    }
+
+    #[test]
+    fn should_localise_note_names() {
+        let german = |name: &str| localise(name, NoteNaming::German);
+        assert_eq!(german("B"), "H");
+        assert_eq!(german("B♭"), "B");
+        assert_eq!(german("B♭♭"), "Heses");
+        assert_eq!(german("B♯"), "His");
+        assert_eq!(german("F♯"), "Fis");
+        assert_eq!(german("C♯♯"), "Cisis");
+        assert_eq!(german("E♭"), "Es");
+        assert_eq!(german("Ab"), "As");
+        assert_eq!(german("D♭"), "Des");
+        assert_eq!(german("E♭♭"), "Eses");
+
+        assert_eq!(localise("G", NoteNaming::Solfege), "Sol");
+        assert_eq!(localise("B♭", NoteNaming::Solfege), "Si♭");
+        assert_eq!(localise("F#", NoteNaming::Solfege), "Fa♯");
+
+        assert_eq!(localise("G", NoteNaming::Nashville(7)), "1");
+        assert_eq!(localise("E", NoteNaming::Nashville(7)), "6");
+        assert_eq!(localise("F", NoteNaming::Nashville(7)), "♭7");
+        assert_eq!(localise("B♭", NoteNaming::English), "B♭");
+        assert_eq!(localise("Hendrix", NoteNaming::Solfege), "Hendrix");
+    }
+
+    #[test]
+    fn should_name_strings_in_any_system() {
+        let names = |naming: NoteNaming, tuning: Tuning| {
+            tuning_names(&Chord {
+                naming,
+                tuning,
+                ..Default::default()
+            })
+        };
+        assert_eq!(
+            names(NoteNaming::German, Tuning::default()),
+            vec!["E", "A", "D", "G", "H", "E"]
+        );
+        assert_eq!(
+            names(NoteNaming::Solfege, Tuning::default()),
+            vec!["Mi", "La", "Re", "Sol", "Si", "Mi"]
+        );
+        // E♭ tuning
+        assert_eq!(
+            names(NoteNaming::German, Tuning(vec![39, 44, 49, 54, 58, 63])),
+            vec!["Es", "As", "Des", "Ges", "B", "Es"]
+        );
+    }
+
+    #[test]
+    fn should_localise_chord_symbols() {
+        let german = |symbol: &str| localise_symbol(symbol, NoteNaming::German);
+        assert_eq!(german("Bm7"), Some(String::from("Hm7")));
+        assert_eq!(german("B♭maj7"), Some(String::from("Bmaj7")));
+        assert_eq!(german("D/F#"), Some(String::from("D/Fis")));
+        assert_eq!(german("Hendrix"), None);
+        assert_eq!(german("Bass riff"), None);
+        assert_eq!(
+            localise_symbol("Am", NoteNaming::Solfege),
+            Some(String::from("Lam"))
+        );
+    }
+
+    #[test]
+    fn should_read_names_in_any_system() {
+        let german = |symbol: &str| to_english(symbol, NoteNaming::German);
+        assert_eq!(german("H7"), Some(String::from("B7")));
+        assert_eq!(german("B"), Some(String::from("B♭")));
+        assert_eq!(german("Fism"), Some(String::from("F♯m")));
+        assert_eq!(german("Es7"), Some(String::from("E♭7")));
+        assert_eq!(german("Asus4"), Some(String::from("Asus4")));
+        assert_eq!(german("C/H"), Some(String::from("C/B")));
+        assert_eq!(german("F#m"), Some(String::from("F#m")));
+        assert_eq!(german("Bbm"), Some(String::from("Bbm")));
+
+        let solfege = |symbol: &str| to_english(symbol, NoteNaming::Solfege);
+        assert_eq!(solfege("Sol7"), Some(String::from("G7")));
+        assert_eq!(solfege("sib"), Some(String::from("Bb")));
+        assert_eq!(solfege("Ré#m"), Some(String::from("D#m")));
+        assert_eq!(solfege("Dm"), Some(String::from("Dm")));
+
+        let nashville = |symbol: &str| to_english(symbol, NoteNaming::Nashville(7));
+        assert_eq!(nashville("6m"), Some(String::from("Em")));
+        assert_eq!(nashville("4"), Some(String::from("C")));
+
+        assert_eq!(to_english("Hendrix", NoteNaming::English), None);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::minify::Element;
+use crate::types::Mode;
+use crate::utils::{get_palette, Palette};
+
+// classes given to each part of the diagram
+const PARTS: [&str; 18] = [
+    "background",
+    "grid",
+    "nut",
+    "barre",
+    "marker",
+    "dot",
+    "dot-label",
+    "finger",
+    "title",
+    "suffix",
+    "subtitle",
+    "tuning",
+    "spelling",
+    "min-fret",
+    "difficulty",
+    "footer",
+    "inlay",
+    "fret-number",
+];
+
+fn properties(palette: &Palette) -> String {
+    format!(
+        ":where(.chord) {{ --chord-fg: {}; --chord-bg: {}; }}",
+        palette.fg, palette.bg
+    )
+}
+
+// :where() so any rule on the page overrides the defaults
+fn stylesheet(mode: Mode) -> String {
+    let mut variables = properties(&get_palette(mode));
+    if mode == Mode::Auto {
+        variables += &format!(
+            "\n    @media (prefers-color-scheme: dark) {{ {} }}",
+            properties(&get_palette(Mode::Dark))
+        );
+    }
+    format!(
+        "<style>
+    {}
+    .chord .background {{ fill: var(--chord-bg); }}
+    .chord .grid, .chord .nut, .chord .barre, .chord .marker, .chord rect.difficulty {{ stroke: var(--chord-fg); }}
+    .chord .title, .chord .suffix, .chord .subtitle, .chord .tuning, .chord .spelling, .chord .finger, .chord .min-fret, .chord .footer, .chord text.difficulty, .chord .inlay, .chord .fret-number {{ fill: var(--chord-fg); }}
+    .chord .dot {{ fill: var(--chord-dot, var(--chord-fg)); }}
+    .chord .dot--root {{ fill: var(--chord-root, var(--chord-dot, var(--chord-fg))); }}
+    .chord .dot--optional {{ fill: transparent; stroke: var(--chord-dot, var(--chord-fg)); }}
+    .chord .dot--optional.dot--root {{ stroke: var(--chord-root, var(--chord-dot, var(--chord-fg))); }}
+    .chord .dot-label {{ fill: var(--chord-bg); }}
+    .chord .dot-label--optional {{ fill: var(--chord-fg); }}
+  </style>",
+        variables
+    )
+}
+
+fn has_class(element: &Element, class: &str) -> bool {
+    element
+        .get("class")
+        .is_some_and(|classes| classes.split_whitespace().any(|name| name == class))
+}
+
+/// Moves palette colours out of the diagram and into CSS custom properties
+/// (`--chord-fg`, `--chord-bg`, `--chord-dot` and `--chord-root`), so the
+/// page can restyle diagrams without generating them again. `Mode::Auto`
+/// switches them to the dark palette when the reader prefers dark.
+pub fn use_css_variables(svg: &str, mode: Mode) -> String {
+    let palette = get_palette(mode);
+    let mut output = String::with_capacity(svg.len());
+    let mut rest = svg;
+    let mut styled = false;
+    // the stylesheet goes after the <title> and <desc>, which should come
+    // first for screen readers
+    let mut pending = false;
+    while let Some(start) = rest.find('<') {
+        let end = if rest[start..].starts_with("<!--") {
+            rest[start..].find("-->").map(|end| start + end + 3)
+        } else {
+            rest[start..].find('>').map(|end| start + end + 1)
+        };
+        let Some(end) = end else {
+            break;
+        };
+        output += &rest[..start];
+
+        let tag = &rest[start..end];
+        let mut element = Element::parse(tag);
+        if pending && !matches!(element.name.as_str(), "title" | "desc") {
+            output += &stylesheet(mode);
+            pending = false;
+        }
+        if tag.starts_with("<!--") || element.closing {
+            output += tag;
+        } else if element.name == "svg" {
+            // the stylesheet only applies inside .chord, custom templates included
+            if !has_class(&element, "chord") {
+                element.add_class("chord");
+            }
+            output += &element.write();
+            // diagrams nested in a row share the outer one's
+            if !styled {
+                pending = true;
+                styled = true;
+            }
+        } else if PARTS.iter().any(|part| has_class(&element, part)) {
+            for attribute in ["fill", "stroke"] {
+                let value = element.get(attribute);
+                if value == Some(palette.fg) || value == Some(palette.bg) {
+                    element.remove(attribute);
+                }
+            }
+            output += &element.write();
+        } else {
+            output += tag;
+        }
+        rest = &rest[end..];
+    }
+    if pending {
+        output += &stylesheet(mode);
+    }
+    output += rest;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{styles::use_css_variables, types::Mode};
+
+    #[test]
+    fn should_remove_palette_colours() {
+        let svg = use_css_variables(
+            "<svg class=\"chord\">
+  <!-- grid -->
+  <line class=\"grid\" stroke=\"#160c1c\" x1=\"50\" />
+  <circle class=\"dot dot--optional\" stroke=\"#160c1c\" fill=\"transparent\" />
+  <text class=\"text title\" fill=\"#160c1c\">A<tspan class=\"suffix\" fill=\"#160c1c\">m</tspan></text>
+  <rect fill=\"#160c1c\" />
+</svg>",
+            Mode::Light,
+        );
+        let (stylesheet, rest) = svg.split_once("</style>").unwrap();
+        assert!(stylesheet.starts_with("<svg class=\"chord\">\n  <style>"));
+        assert!(stylesheet.contains("--chord-fg: #160c1c; --chord-bg: #FBF6E2;"));
+
+        assert_eq!(
+            rest,
+            "<!-- grid -->
+  <line class=\"grid\" x1=\"50\"/>
+  <circle class=\"dot dot--optional\" fill=\"transparent\"/>
+  <text class=\"text title\">A<tspan class=\"suffix\">m</tspan></text>
+  <rect fill=\"#160c1c\" />
+</svg>"
+        );
+    }
+
+    #[test]
+    fn should_add_chord_class() {
+        let svg = use_css_variables("<svg width=\"300px\"></svg>", Mode::Light);
+        assert!(svg.starts_with("<svg width=\"300px\" class=\"chord\"><style>"));
+    }
+
+    #[test]
+    fn should_add_one_stylesheet() {
+        let svg = use_css_variables(
+            "<svg><svg class=\"chord\"></svg><svg class=\"chord\"></svg></svg>",
+            Mode::Light,
+        );
+        assert_eq!(svg.matches("<style>").count(), 1);
+    }
+
+    #[test]
+    fn should_switch_to_dark() {
+        let light = use_css_variables("<svg></svg>", Mode::Light);
+        assert!(!light.contains("prefers-color-scheme"));
+
+        let auto = use_css_variables("<svg></svg>", Mode::Auto);
+        assert!(auto.contains(":where(.chord) { --chord-fg: #160c1c; --chord-bg: #FBF6E2; }
+    @media (prefers-color-scheme: dark) { :where(.chord) { --chord-fg: #FBF6E2; --chord-bg: #160c1c; } }"));
+    }
+
+    #[test]
+    fn should_keep_title_and_desc_first() {
+        let svg = use_css_variables(
+            "<svg class=\"chord\">
+  <title id=\"title-1\">A chord diagram</title>
+  <desc id=\"desc-1\">A: open A.</desc>
+  <line class=\"grid\" stroke=\"#160c1c\" />
+</svg>",
+            Mode::Light,
+        );
+        let position = |tag: &str| svg.find(tag).unwrap();
+        assert!(position("<title") < position("<desc"));
+        assert!(position("<desc") < position("<style>"));
+        assert!(position("<style>") < position("<line"));
+    }
+}
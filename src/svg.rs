@@ -1,13 +1,14 @@
 use crate::{
-    utils::{find_all, get_note_coords, Palette},
+    types::{Footer, Fret},
+    utils::{escape_xml, find_all, get_note_coords, Palette, Transform},
     Chord, GuitarString,
 };
 
-pub fn svg_draw_bg(use_background: bool, palette: &Palette) -> String {
+pub fn svg_draw_bg(use_background: bool, width: &i32, height: &i32, palette: &Palette) -> String {
     if use_background {
         format!(
-            "<rect fill=\"{}\" width=\"300\" height=\"310\" rx=\"10\" />",
-            palette.bg
+            "<rect class=\"background\" fill=\"{}\" width=\"{}\" height=\"{}\" rx=\"10\" />",
+            palette.bg, width, height
         )
     } else {
         "".into()
@@ -21,34 +22,181 @@ pub fn svg_draw_finger(
     palette: &Palette,
 ) -> String {
     let x = 50 + (i as i32 * string_space);
-    let y = if finger == "0" || finger == "x" {
-        35
-    } else {
-        265
-    };
+    let y = 265;
 
     format!(
-        "<text x=\"{}\" y=\"{}\" class=\"text\" dominant-baseline=\"middle\" text-anchor=\"middle\" font-size=\"16\" fill=\"{}\" font-weight=\"400\">{}</text>",
+        "<text x=\"{}\" y=\"{}\" class=\"text finger\" dominant-baseline=\"middle\" text-anchor=\"middle\" font-size=\"16\" fill=\"{}\" font-weight=\"400\">{}</text>",
         x, y, palette.fg, finger
     )
 }
 
+/// Marker above the nut for strings which aren't fretted
+pub fn svg_draw_string_marker(
+    fret: &Fret,
+    string: GuitarString,
+    string_space: &i32,
+    palette: &Palette,
+) -> String {
+    let x = 50 + (string as i32 * string_space);
+    let y = 35;
+    let size = 6;
+
+    match fret {
+        Fret::Open => format!(
+            "<circle class=\"marker marker--open\" cx=\"{}\" cy=\"{}\" r=\"7\" stroke=\"{}\" stroke-width=\"2\" fill=\"transparent\" />",
+            x, y, palette.fg
+        ),
+        Fret::Optional(0) => format!(
+            "<circle class=\"marker marker--optional\" cx=\"{}\" cy=\"{}\" r=\"7\" stroke=\"{}\" stroke-width=\"2\" stroke-dasharray=\"3 3\" fill=\"transparent\" opacity=\"0.6\" />",
+            x, y, palette.fg
+        ),
+        Fret::Muted => format!(
+            "<path class=\"marker marker--muted\" d=\"M {} {} L {} {} M {} {} L {} {}\" stroke=\"{}\" stroke-width=\"2\" stroke-linecap=\"round\" />",
+            x - size,
+            y - size,
+            x + size,
+            y + size,
+            x + size,
+            y - size,
+            x - size,
+            y + size,
+            palette.fg
+        ),
+        _ => "".to_string(),
+    }
+}
+
+/// Open string in a scale, filled in for the root
+pub fn svg_draw_open_note(
+    string: GuitarString,
+    string_space: &i32,
+    root: bool,
+    palette: &Palette,
+) -> String {
+    if !root {
+        return svg_draw_string_marker(&Fret::Open, string, string_space, palette);
+    }
+    format!(
+        "<circle class=\"marker marker--open dot--root\" cx=\"{}\" cy=\"35\" r=\"7\" stroke=\"{}\" stroke-width=\"2\" fill=\"{}\" />",
+        50 + (string as i32 * string_space),
+        palette.fg,
+        palette.fg
+    )
+}
+
+/// Strings and frets for a neck `frets` frets long, as drawn by the template
+pub fn svg_draw_grid(frets: i32, string_space: &i32, show_nut: bool, palette: &Palette) -> String {
+    let top = 50;
+    let bottom = top + frets * string_space;
+    let mut grid = String::new();
+    for string in 0..6 {
+        let x = 50 + string * string_space;
+        grid += &format!(
+            "<line class=\"grid\" stroke-width=\"2\" stroke=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" />",
+            palette.fg, x, top, x, bottom
+        );
+    }
+
+    let (nut_width, nut_shape) = if show_nut { (9, "round") } else { (2, "butt") };
+    let right = 50 + 5 * string_space;
+    grid += &format!(
+        "<line class=\"nut\" stroke-width=\"{}\" stroke=\"{}\" x1=\"49\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-linecap=\"{}\" />",
+        nut_width,
+        palette.fg,
+        top,
+        right + 1,
+        top,
+        nut_shape
+    );
+    for fret in 1..=frets {
+        let y = top + fret * string_space;
+        // the last fret reaches the corners
+        let (left, right) = if fret == frets {
+            (49, right + 1)
+        } else {
+            (50, right)
+        };
+        grid += &format!(
+            "<line class=\"grid\" stroke-width=\"2\" stroke=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" />",
+            palette.fg, left, y, right, y
+        );
+    }
+    grid
+}
+
+/// Note on a sideways neck, square for the root. Faded notes are outside the
+/// chord shape being shown.
+pub fn svg_draw_neck_note(x: i32, y: i32, root: bool, faded: bool, palette: &Palette) -> String {
+    let opacity = if faded { " opacity=\"0.3\"" } else { "" };
+    if root {
+        format!(
+            "<rect class=\"dot dot--root\" x=\"{}\" y=\"{}\" width=\"20\" height=\"20\" rx=\"4\" fill=\"{}\"{} />",
+            x - 10,
+            y - 10,
+            palette.fg,
+            opacity
+        )
+    } else {
+        format!(
+            "<circle class=\"dot\" cx=\"{}\" cy=\"{}\" r=\"10\" fill=\"{}\"{} />",
+            x, y, palette.fg, opacity
+        )
+    }
+}
+
+pub fn svg_draw_line(
+    class: &str,
+    stroke_width: i32,
+    (x1, y1): (i32, i32),
+    (x2, y2): (i32, i32),
+    palette: &Palette,
+) -> String {
+    format!(
+        "<line class=\"{}\" stroke-width=\"{}\" stroke=\"{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" />",
+        class, stroke_width, palette.fg, x1, y1, x2, y2
+    )
+}
+
+/// Position marker set into the neck
+pub fn svg_draw_inlay(x: i32, y: i32, palette: &Palette) -> String {
+    format!(
+        "<circle class=\"inlay\" cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"{}\" opacity=\"0.25\" />",
+        x, y, palette.fg
+    )
+}
+
+pub fn svg_draw_fret_number(fret: i32, x: i32, y: i32, palette: &Palette) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" class=\"text fret-number\" text-anchor=\"middle\" font-size=\"12\" fill=\"{}\" font-weight=\"400\">{}</text>",
+        x, y, palette.fg, fret
+    )
+}
+
 pub fn svg_draw_min_fret(min_fret: &i32, string_space: &i32, palette: &Palette) -> String {
     let offset_top = 50;
 
     let x = 32;
     let y = string_space * 2 + offset_top - (string_space / 2);
     format!(
-        "<text x=\"{}\" y=\"{}\" class=\"text\" dominant-baseline=\"middle\" text-anchor=\"end\" font-size=\"16\" fill=\"{}\" font-weight=\"400\">{}</text>",
+        "<text x=\"{}\" y=\"{}\" class=\"text min-fret\" dominant-baseline=\"middle\" text-anchor=\"end\" font-size=\"16\" fill=\"{}\" font-weight=\"400\">{}</text>",
         x, y, palette.fg, min_fret
     )
 }
 
+fn dot_class(root: bool) -> &'static str {
+    if root {
+        "dot dot--root"
+    } else {
+        "dot"
+    }
+}
+
 pub fn svg_draw_note(
     note: &i32,
     string: GuitarString,
     string_space: &i32,
     min_fret: &i32,
+    root: bool,
     palette: &Palette,
 ) -> String {
     if note <= &0 {
@@ -58,16 +206,93 @@ pub fn svg_draw_note(
 
     let (x, y) = get_note_coords(note, string, string_space, min_fret);
     format!(
-        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" />",
-        x, y, radius, palette.fg
+        "<circle class=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" />",
+        dot_class(root),
+        x,
+        y,
+        radius,
+        palette.fg
+    )
+}
+
+/// Root of a scale, square so it stands out from the other notes
+pub fn svg_draw_root_note(
+    note: &i32,
+    string: GuitarString,
+    string_space: &i32,
+    min_fret: &i32,
+    palette: &Palette,
+) -> String {
+    if note <= &0 {
+        return "".to_string();
+    }
+    let size = 24;
+
+    let (x, y) = get_note_coords(note, string, string_space, min_fret);
+    format!(
+        "<rect class=\"dot dot--root\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"5\" fill=\"{}\" />",
+        x - size / 2,
+        y - size / 2,
+        size,
+        size,
+        palette.fg
+    )
+}
+
+/// Hollow dot for notes which can be left out
+pub fn svg_draw_optional_note(
+    note: &i32,
+    string: GuitarString,
+    string_space: &i32,
+    min_fret: &i32,
+    root: bool,
+    palette: &Palette,
+) -> String {
+    if note <= &0 {
+        return "".to_string();
+    }
+    let radius = 12;
+
+    let (x, y) = get_note_coords(note, string, string_space, min_fret);
+    format!(
+        "<circle class=\"{} dot--optional\" cx=\"{}\" cy=\"{}\" r=\"{}\" stroke=\"{}\" stroke-width=\"2\" fill=\"transparent\" />",
+        dot_class(root),
+        x,
+        y,
+        radius,
+        palette.fg
+    )
+}
+
+/// Note name on a dot, in the background colour unless the dot is hollow
+pub fn svg_draw_dot_label(
+    name: &str,
+    x: &i32,
+    y: &i32,
+    optional: bool,
+    palette: &Palette,
+) -> String {
+    let (class, fill) = if optional {
+        ("text dot-label dot-label--optional", palette.fg)
+    } else {
+        ("text dot-label", palette.bg)
+    };
+    format!(
+        "<text x=\"{}\" y=\"{}\" class=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-size=\"12\" fill=\"{}\" font-weight=\"600\">{}</text>",
+        x,
+        y,
+        class,
+        fill,
+        escape_xml(name)
     )
 }
 
 pub fn svg_draw_barres(
     barre_fret: &i32,
-    frets: &[i32],
+    frets: &[Fret],
     string_space: &i32,
     min_fret: &i32,
+    transform: &Transform,
     palette: &Palette,
 ) -> String {
     let strings = find_all(frets, barre_fret);
@@ -75,15 +300,22 @@ pub fn svg_draw_barres(
         return String::from("");
     }
 
+    // left to right as drawn
+    let mut columns: Vec<usize> = strings
+        .iter()
+        .map(|string| transform.string(*string) as usize)
+        .collect();
+    columns.sort();
+
     let first = get_note_coords(
         barre_fret,
-        (*strings.first().unwrap_or(&0)).into(),
+        (*columns.first().unwrap_or(&0)).into(),
         string_space,
         min_fret,
     );
     let last = get_note_coords(
         barre_fret,
-        (*strings.last().unwrap_or(&0)).into(),
+        (*columns.last().unwrap_or(&0)).into(),
         string_space,
         min_fret,
     );
@@ -100,7 +332,7 @@ pub fn svg_draw_barres(
     let end_control = (last.0 - control_x_offset, last.1 - control_y_offset);
 
     format!(
-        "<path d=\"M {} {} C {} {}, {} {}, {} {}\" stroke=\"{}\" stroke-width=\"3\" fill=\"transparent\" stroke-linecap=\"round\" />",
+        "<path class=\"barre\" d=\"M {} {} C {} {}, {} {}, {} {}\" stroke=\"{}\" stroke-width=\"3\" fill=\"transparent\" stroke-linecap=\"round\" />",
         first.0,
         first.1 - y_offset,
         origin_control.0,
@@ -113,11 +345,104 @@ pub fn svg_draw_barres(
     )
 }
 
+pub fn svg_draw_difficulty(grade: u8, palette: &Palette) -> String {
+    format!(
+        "<rect class=\"difficulty\" x=\"252\" y=\"6\" width=\"42\" height=\"22\" rx=\"11\" stroke=\"{}\" stroke-width=\"1.5\" fill=\"transparent\" /><text x=\"273\" y=\"18\" class=\"text difficulty\" dominant-baseline=\"middle\" text-anchor=\"middle\" font-size=\"12\" fill=\"{}\" font-weight=\"400\">{}/5</text>",
+        palette.fg, palette.fg, grade
+    )
+}
+
+pub fn svg_draw_footer(footer: &Footer, height: &i32, palette: &Palette) -> String {
+    let text = |text: &str| {
+        format!(
+            "<text x=\"150\" y=\"{}\" class=\"text footer\" text-anchor=\"middle\" font-size=\"12\" fill=\"{}\" font-weight=\"400\">{}</text>",
+            height - 10,
+            palette.fg,
+            escape_xml(text)
+        )
+    };
+
+    match footer {
+        Footer::Default => text("chordgenerator.xyz"),
+        Footer::Text(footer) => text(footer),
+        Footer::Link { text: footer, href } => {
+            format!("<a href=\"{}\">{}</a>", escape_xml(href), text(footer))
+        }
+        Footer::None => String::from(""),
+    }
+}
+
+/// Open note of each string, in a row under the grid
+pub fn svg_draw_tuning(
+    names: &[String],
+    transform: &Transform,
+    string_space: &i32,
+    top: &i32,
+    palette: &Palette,
+) -> String {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                "<text x=\"{}\" y=\"{}\" class=\"text tuning\" text-anchor=\"middle\" font-size=\"12\" fill=\"{}\" font-weight=\"300\">{}</text>",
+                50 + transform.string(i) as i32 * string_space,
+                top + 14,
+                palette.fg,
+                escape_xml(name)
+            )
+        })
+        .collect()
+}
+
+/// Panel under the grid with the note each string plays, and its interval
+/// above the root underneath
+pub fn svg_draw_spelling(
+    spelling: &[Option<(String, String)>],
+    transform: &Transform,
+    string_space: &i32,
+    top: &i32,
+    palette: &Palette,
+) -> String {
+    let mut panel = String::new();
+    for (i, (note, interval)) in spelling
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| entry.as_ref().map(|entry| (i, entry)))
+    {
+        let x = 50 + transform.string(i) as i32 * string_space;
+        panel += &format!(
+            "<text x=\"{}\" y=\"{}\" class=\"text spelling\" text-anchor=\"middle\" font-size=\"14\" fill=\"{}\" font-weight=\"400\">{}</text>",
+            x,
+            top + 14,
+            palette.fg,
+            escape_xml(note)
+        );
+        panel += &format!(
+            "<text x=\"{}\" y=\"{}\" class=\"text spelling spelling--interval\" text-anchor=\"middle\" font-size=\"12\" fill=\"{}\" font-weight=\"300\">{}</text>",
+            x,
+            top + 32,
+            palette.fg,
+            escape_xml(interval)
+        );
+    }
+    panel
+}
+
+pub fn svg_draw_subtitle(subtitle: &str, y: &i32, palette: &Palette) -> String {
+    format!(
+        "<text x=\"150\" y=\"{}\" class=\"text subtitle\" text-anchor=\"middle\" font-size=\"14\" fill=\"{}\" font-weight=\"400\">{}</text>",
+        y,
+        palette.fg,
+        escape_xml(subtitle)
+    )
+}
+
 pub fn svg_draw_title(chord_settings: &Chord, palette: &Palette) -> String {
     match (chord_settings.title, chord_settings.suffix) {
         (Some(title), Some(suffix)) => format!(
-            "<text x=\"150px\" y=\"{}\" class=\"text\" dominant-baseline=\"middle\"
-        text-anchor=\"middle\" font-size=\"24\" fill=\"{}\" font-weight=\"400\">{}<tspan font-size=\"18\" fill=\"{}\" font-weight=\"300\">{}</tspan></text>",
+            "<text x=\"150px\" y=\"{}\" class=\"text title\" dominant-baseline=\"middle\"
+        text-anchor=\"middle\" font-size=\"24\" fill=\"{}\" font-weight=\"400\">{}<tspan class=\"suffix\" font-size=\"18\" fill=\"{}\" font-weight=\"300\">{}</tspan></text>",
             18,
             palette.fg,
             title,
@@ -125,7 +450,7 @@ pub fn svg_draw_title(chord_settings: &Chord, palette: &Palette) -> String {
             suffix
         ),
         (Some(title), None) => format!(
-            "<text x=\"150px\" y=\"{}\" class=\"text\" dominant-baseline=\"middle\"
+            "<text x=\"150px\" y=\"{}\" class=\"text title\" dominant-baseline=\"middle\"
   text-anchor=\"middle\" font-size=\"24\" fill=\"{}\" font-weight=\"400\">{}</text>",
             18,
             palette.fg,
@@ -138,8 +463,14 @@ pub fn svg_draw_title(chord_settings: &Chord, palette: &Palette) -> String {
 #[cfg(test)]
 mod tests {
     use crate::{
-        svg::{svg_draw_barres, svg_draw_note},
-        utils::Palette,
+        svg::{
+            svg_draw_barres, svg_draw_difficulty, svg_draw_dot_label, svg_draw_footer,
+            svg_draw_grid, svg_draw_neck_note, svg_draw_note, svg_draw_open_note,
+            svg_draw_optional_note, svg_draw_root_note, svg_draw_spelling, svg_draw_string_marker,
+            svg_draw_subtitle, svg_draw_tuning,
+        },
+        types::{frets_from_numbers, Footer, Fret, Hand},
+        utils::{Palette, Transform},
         Chord,
     };
 
@@ -151,59 +482,107 @@ mod tests {
             fg: "#fff",
             bg: "#111",
         };
-        let note = svg_draw_note(&6, crate::GuitarString::D, &10, &0, &palette);
-        let expected = "<circle cx=\"70\" cy=\"105\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&6, crate::GuitarString::D, &10, &0, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"70\" cy=\"105\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&2, crate::GuitarString::E, &12, &1, &palette);
-        let expected = "<circle cx=\"50\" cy=\"68\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&2, crate::GuitarString::E, &12, &1, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"50\" cy=\"68\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&7, crate::GuitarString::A, &14, &2, &palette);
-        let expected = "<circle cx=\"64\" cy=\"141\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&7, crate::GuitarString::A, &14, &2, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"64\" cy=\"141\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&4, crate::GuitarString::G, &20, &3, &palette);
-        let expected = "<circle cx=\"110\" cy=\"100\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&4, crate::GuitarString::G, &20, &3, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"110\" cy=\"100\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&9, crate::GuitarString::B, &30, &5, &palette);
-        let expected = "<circle cx=\"170\" cy=\"215\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&9, crate::GuitarString::B, &30, &5, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"170\" cy=\"215\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&12, crate::GuitarString::HighE, &32, &10, &palette);
-        let expected = "<circle cx=\"210\" cy=\"162\" r=\"13\" fill=\"#fff\" />";
+        let note = svg_draw_note(&12, crate::GuitarString::HighE, &32, &10, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"210\" cy=\"162\" r=\"13\" fill=\"#fff\" />";
+        assert_eq!(note, expected);
+
+        let note = svg_draw_note(&12, crate::GuitarString::HighE, &32, &10, true, &palette);
+        let expected =
+            "<circle class=\"dot dot--root\" cx=\"210\" cy=\"162\" r=\"13\" fill=\"#fff\" />";
         assert_eq!(note, expected);
 
         let palette = Palette {
             fg: "#111",
             bg: "#333",
         };
-        let note = svg_draw_note(&6, crate::GuitarString::D, &10, &0, &palette);
-        let expected = "<circle cx=\"70\" cy=\"105\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&6, crate::GuitarString::D, &10, &0, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"70\" cy=\"105\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&2, crate::GuitarString::E, &12, &1, &palette);
-        let expected = "<circle cx=\"50\" cy=\"68\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&2, crate::GuitarString::E, &12, &1, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"50\" cy=\"68\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&7, crate::GuitarString::A, &14, &2, &palette);
-        let expected = "<circle cx=\"64\" cy=\"141\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&7, crate::GuitarString::A, &14, &2, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"64\" cy=\"141\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&4, crate::GuitarString::G, &20, &3, &palette);
-        let expected = "<circle cx=\"110\" cy=\"100\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&4, crate::GuitarString::G, &20, &3, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"110\" cy=\"100\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&9, crate::GuitarString::B, &30, &5, &palette);
-        let expected = "<circle cx=\"170\" cy=\"215\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&9, crate::GuitarString::B, &30, &5, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"170\" cy=\"215\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
 
-        let note = svg_draw_note(&12, crate::GuitarString::HighE, &32, &10, &palette);
-        let expected = "<circle cx=\"210\" cy=\"162\" r=\"13\" fill=\"#111\" />";
+        let note = svg_draw_note(&12, crate::GuitarString::HighE, &32, &10, false, &palette);
+        let expected = "<circle class=\"dot\" cx=\"210\" cy=\"162\" r=\"13\" fill=\"#111\" />";
         assert_eq!(note, expected);
     }
 
+    #[test]
+    fn should_render_optional_note() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        let note = svg_draw_optional_note(&7, crate::GuitarString::A, &14, &2, false, &palette);
+        let expected = "<circle class=\"dot dot--optional\" cx=\"64\" cy=\"141\" r=\"12\" stroke=\"#fff\" stroke-width=\"2\" fill=\"transparent\" />";
+        assert_eq!(note, expected);
+
+        let note = svg_draw_optional_note(&7, crate::GuitarString::A, &14, &2, true, &palette);
+        let expected = "<circle class=\"dot dot--root dot--optional\" cx=\"64\" cy=\"141\" r=\"12\" stroke=\"#fff\" stroke-width=\"2\" fill=\"transparent\" />";
+        assert_eq!(note, expected);
+    }
+
+    #[test]
+    fn should_render_string_markers() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_string_marker(&Fret::Open, crate::GuitarString::A, &40, &palette),
+            "<circle class=\"marker marker--open\" cx=\"90\" cy=\"35\" r=\"7\" stroke=\"#fff\" stroke-width=\"2\" fill=\"transparent\" />"
+        );
+        assert_eq!(
+            svg_draw_string_marker(&Fret::Optional(0), crate::GuitarString::A, &40, &palette),
+            "<circle class=\"marker marker--optional\" cx=\"90\" cy=\"35\" r=\"7\" stroke=\"#fff\" stroke-width=\"2\" stroke-dasharray=\"3 3\" fill=\"transparent\" opacity=\"0.6\" />"
+        );
+        assert_eq!(
+            svg_draw_string_marker(&Fret::Muted, crate::GuitarString::E, &40, &palette),
+            "<path class=\"marker marker--muted\" d=\"M 44 29 L 56 41 M 56 29 L 44 41\" stroke=\"#fff\" stroke-width=\"2\" stroke-linecap=\"round\" />"
+        );
+        assert_eq!(
+            svg_draw_string_marker(&Fret::Unplayed, crate::GuitarString::E, &40, &palette),
+            ""
+        );
+        assert_eq!(
+            svg_draw_string_marker(&Fret::Fretted(3), crate::GuitarString::E, &40, &palette),
+            ""
+        );
+    }
+
     #[test]
     fn should_draw_barre() {
         let palette = Palette {
@@ -211,11 +590,44 @@ mod tests {
             bg: "#333",
         };
 
-        let barre = svg_draw_barres(&5, &vec![5, 7, 7, 6, 5, -1], &40, &5, &palette);
-        let expected = "<path d=\"M 50 87 C 58 77, 202 77, 210 87\" stroke=\"#efe\" stroke-width=\"3\" fill=\"transparent\" stroke-linecap=\"round\" />";
+        let right = Transform::new(Hand::Right);
+        let barre = svg_draw_barres(
+            &5,
+            &frets_from_numbers(&[5, 7, 7, 6, 5, -1]),
+            &40,
+            &5,
+            &right,
+            &palette,
+        );
+        let expected = "<path class=\"barre\" d=\"M 50 87 C 58 77, 202 77, 210 87\" stroke=\"#efe\" stroke-width=\"3\" fill=\"transparent\" stroke-linecap=\"round\" />";
+        assert_eq!(barre, expected);
+
+        // mirrored, still drawn left to right
+        let left = Transform::new(Hand::Left);
+        let barre = svg_draw_barres(
+            &5,
+            &frets_from_numbers(&[5, 7, 7, 6, 5, -1]),
+            &40,
+            &5,
+            &left,
+            &palette,
+        );
+        let expected = "<path class=\"barre\" d=\"M 90 87 C 98 77, 242 77, 250 87\" stroke=\"#efe\" stroke-width=\"3\" fill=\"transparent\" stroke-linecap=\"round\" />";
         assert_eq!(barre, expected);
     }
 
+    #[test]
+    fn should_draw_difficulty() {
+        let palette = Palette {
+            fg: "#efe",
+            bg: "#333",
+        };
+
+        let badge = svg_draw_difficulty(3, &palette);
+        let expected = "<rect class=\"difficulty\" x=\"252\" y=\"6\" width=\"42\" height=\"22\" rx=\"11\" stroke=\"#efe\" stroke-width=\"1.5\" fill=\"transparent\" /><text x=\"273\" y=\"18\" class=\"text difficulty\" dominant-baseline=\"middle\" text-anchor=\"middle\" font-size=\"12\" fill=\"#efe\" font-weight=\"400\">3/5</text>";
+        assert_eq!(badge, expected);
+    }
+
     #[test]
     fn should_draw_titke() {
         let palette = Palette {
@@ -233,7 +645,7 @@ mod tests {
         // no suffix
         assert_eq!(
             svg_draw_title(&chord, &palette),
-            "<text x=\"150px\" y=\"18\" class=\"text\" dominant-baseline=\"middle\"
+            "<text x=\"150px\" y=\"18\" class=\"text title\" dominant-baseline=\"middle\"
   text-anchor=\"middle\" font-size=\"24\" fill=\"#efe\" font-weight=\"400\">C</text>",
         );
 
@@ -243,7 +655,158 @@ mod tests {
             suffix: Some(&suffix),
             ..Default::default()
         };
-        assert_eq!(svg_draw_title(&chord, &palette), "<text x=\"150px\" y=\"18\" class=\"text\" dominant-baseline=\"middle\"
-        text-anchor=\"middle\" font-size=\"24\" fill=\"#efe\" font-weight=\"400\">C<tspan font-size=\"18\" fill=\"#efe\" font-weight=\"300\">aug9</tspan></text>");
+        assert_eq!(svg_draw_title(&chord, &palette), "<text x=\"150px\" y=\"18\" class=\"text title\" dominant-baseline=\"middle\"
+        text-anchor=\"middle\" font-size=\"24\" fill=\"#efe\" font-weight=\"400\">C<tspan class=\"suffix\" font-size=\"18\" fill=\"#efe\" font-weight=\"300\">aug9</tspan></text>");
+    }
+
+    #[test]
+    fn should_render_footer() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_footer(&Footer::Default, &310, &palette),
+            "<text x=\"150\" y=\"300\" class=\"text footer\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"400\">chordgenerator.xyz</text>"
+        );
+        assert_eq!(
+            svg_draw_footer(&Footer::Text(String::from("Tom & Jerry")), &310, &palette),
+            "<text x=\"150\" y=\"300\" class=\"text footer\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"400\">Tom &amp; Jerry</text>"
+        );
+        assert_eq!(
+            svg_draw_footer(
+                &Footer::Link {
+                    text: String::from("example.com"),
+                    href: String::from("https://example.com/?a=1&b=2")
+                },
+                &310,
+                &palette
+            ),
+            "<a href=\"https://example.com/?a=1&amp;b=2\"><text x=\"150\" y=\"300\" class=\"text footer\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"400\">example.com</text></a>"
+        );
+        assert_eq!(svg_draw_footer(&Footer::None, &280, &palette), "");
+    }
+
+    #[test]
+    fn should_render_subtitle() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_subtitle("1st inversion", &290, &palette),
+            "<text x=\"150\" y=\"290\" class=\"text subtitle\" text-anchor=\"middle\" font-size=\"14\" fill=\"#fff\" font-weight=\"400\">1st inversion</text>"
+        );
+    }
+
+    #[test]
+    fn should_render_dot_labels() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_dot_label("F♯", &90, &70, false, &palette),
+            "<text x=\"90\" y=\"70\" class=\"text dot-label\" text-anchor=\"middle\" dominant-baseline=\"central\" font-size=\"12\" fill=\"#111\" font-weight=\"600\">F♯</text>"
+        );
+        assert!(svg_draw_dot_label("B♭", &90, &70, true, &palette)
+            .contains("class=\"text dot-label dot-label--optional\" text-anchor=\"middle\" dominant-baseline=\"central\" font-size=\"12\" fill=\"#fff\""));
+    }
+
+    #[test]
+    fn should_render_spelling_panel() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        let spelling = vec![None, Some((String::from("A"), String::from("R")))];
+        assert_eq!(
+            svg_draw_spelling(&spelling, &Transform::new(Hand::Right), &40, &260, &palette),
+            "<text x=\"90\" y=\"274\" class=\"text spelling\" text-anchor=\"middle\" font-size=\"14\" fill=\"#fff\" font-weight=\"400\">A</text><text x=\"90\" y=\"292\" class=\"text spelling spelling--interval\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"300\">R</text>"
+        );
+        assert!(
+            svg_draw_spelling(&spelling, &Transform::new(Hand::Left), &40, &260, &palette)
+                .starts_with("<text x=\"210\"")
+        );
+    }
+
+    #[test]
+    fn should_render_tuning() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        let names = vec![String::from("E"), String::from("A")];
+        assert_eq!(
+            svg_draw_tuning(&names, &Transform::new(Hand::Right), &40, &280, &palette),
+            "<text x=\"50\" y=\"294\" class=\"text tuning\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"300\">E</text><text x=\"90\" y=\"294\" class=\"text tuning\" text-anchor=\"middle\" font-size=\"12\" fill=\"#fff\" font-weight=\"300\">A</text>"
+        );
+        assert!(
+            svg_draw_tuning(&names, &Transform::new(Hand::Left), &40, &280, &palette)
+                .starts_with("<text x=\"250\"")
+        );
+    }
+
+    #[test]
+    fn should_render_root_note() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        let note = svg_draw_root_note(&7, crate::GuitarString::A, &14, &2, &palette);
+        let expected = "<rect class=\"dot dot--root\" x=\"52\" y=\"129\" width=\"24\" height=\"24\" rx=\"5\" fill=\"#fff\" />";
+        assert_eq!(note, expected);
+        assert_eq!(
+            svg_draw_root_note(&0, crate::GuitarString::A, &14, &2, &palette),
+            ""
+        );
+    }
+
+    #[test]
+    fn should_render_open_note() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_open_note(crate::GuitarString::A, &40, false, &palette),
+            svg_draw_string_marker(&Fret::Open, crate::GuitarString::A, &40, &palette)
+        );
+        assert_eq!(
+            svg_draw_open_note(crate::GuitarString::A, &40, true, &palette),
+            "<circle class=\"marker marker--open dot--root\" cx=\"90\" cy=\"35\" r=\"7\" stroke=\"#fff\" stroke-width=\"2\" fill=\"#fff\" />"
+        );
+    }
+
+    #[test]
+    fn should_render_grid() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        let grid = svg_draw_grid(7, &40, true, &palette);
+        assert_eq!(grid.matches("<line").count(), 6 + 8);
+        assert!(grid.contains("<line class=\"grid\" stroke-width=\"2\" stroke=\"#fff\" x1=\"250\" y1=\"50\" x2=\"250\" y2=\"330\" />"));
+        assert!(grid.contains("<line class=\"nut\" stroke-width=\"9\" stroke=\"#fff\" x1=\"49\" y1=\"50\" x2=\"251\" y2=\"50\" stroke-linecap=\"round\" />"));
+        assert!(grid.ends_with("<line class=\"grid\" stroke-width=\"2\" stroke=\"#fff\" x1=\"49\" y1=\"330\" x2=\"251\" y2=\"330\" />"));
+
+        let grid = svg_draw_grid(5, &40, false, &palette);
+        assert!(grid.contains("<line class=\"nut\" stroke-width=\"2\" stroke=\"#fff\" x1=\"49\" y1=\"50\" x2=\"251\" y2=\"50\" stroke-linecap=\"butt\" />"));
+    }
+
+    #[test]
+    fn should_render_neck_note() {
+        let palette = Palette {
+            fg: "#fff",
+            bg: "#111",
+        };
+        assert_eq!(
+            svg_draw_neck_note(85, 50, false, false, &palette),
+            "<circle class=\"dot\" cx=\"85\" cy=\"50\" r=\"10\" fill=\"#fff\" />"
+        );
+        assert_eq!(
+            svg_draw_neck_note(85, 50, true, true, &palette),
+            "<rect class=\"dot dot--root\" x=\"75\" y=\"40\" width=\"20\" height=\"20\" rx=\"4\" fill=\"#fff\" opacity=\"0.3\" />"
+        );
     }
 }
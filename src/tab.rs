@@ -0,0 +1,204 @@
+use crate::spelling::tuning_names;
+use crate::types::{Chord, Fret};
+
+fn tab_cell(fret: &Fret) -> String {
+    match fret {
+        Fret::Muted => "x".to_string(),
+        Fret::Unplayed => "-".to_string(),
+        Fret::Open => "0".to_string(),
+        Fret::Fretted(fret) => fret.to_string(),
+        Fret::Optional(fret) => format!("({})", fret),
+    }
+}
+
+// strings past the end of the frets aren't played
+fn tab_column(chord: &Chord, strings: usize) -> Vec<String> {
+    let cells: Vec<String> = (0..strings)
+        .rev()
+        .map(|string| tab_cell(chord.frets.get(string).unwrap_or(&Fret::Unplayed)))
+        .collect();
+    let width = cells.iter().map(|cell| cell.len()).max().unwrap_or(1);
+
+    cells
+        .iter()
+        .map(|cell| format!("-{:-<width$}-", cell, width = width))
+        .collect()
+}
+
+// open string names top to bottom, with the top one in lower case when it
+// matches the bottom, as in e|
+fn string_names(chord: &Chord, strings: usize) -> Vec<String> {
+    let mut names = tuning_names(chord);
+    names.resize(strings, String::new());
+    names.reverse();
+    if names.len() > 1 && names.first() == names.last() {
+        names[0] = names[0].to_lowercase();
+    }
+    let width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    names
+        .iter()
+        .map(|name| format!("{:<width$}", name, width = width))
+        .collect()
+}
+
+/// Writes bars of chords as a tab staff, one column per chord with a bar
+/// line between each bar. Lines are named from the first chord's tuning.
+pub fn tab_staff(bars: &[&[Chord]]) -> String {
+    let default = Chord::default();
+    let first = bars
+        .iter()
+        .flat_map(|bar| bar.iter())
+        .next()
+        .unwrap_or(&default);
+    let strings = bars
+        .iter()
+        .flat_map(|bar| bar.iter())
+        .map(|chord| chord.frets.len())
+        .chain([first.tuning.0.len()])
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<String> = string_names(first, strings)
+        .iter()
+        .map(|name| format!("{}|", name))
+        .collect();
+
+    for bar in bars {
+        for chord in bar.iter() {
+            for (line, cell) in lines.iter_mut().zip(tab_column(chord, strings)) {
+                *line += &cell;
+            }
+        }
+        for line in lines.iter_mut() {
+            *line += "|";
+        }
+    }
+
+    lines.join("\n")
+}
+
+impl Chord<'_> {
+    /// Chord as a single column of tab
+    pub fn to_tab(&self) -> String {
+        tab_staff(&[std::slice::from_ref(self)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tab::tab_staff,
+        types::{frets_from_numbers, Chord, Fret, Tuning},
+    };
+
+    #[test]
+    fn should_render_chord_as_tab() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 3, 2, 0, 1, 0]),
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "e|-0-|\nB|-1-|\nG|-0-|\nD|-2-|\nA|-3-|\nE|-x-|"
+        );
+    }
+
+    #[test]
+    fn should_pad_double_digit_frets() {
+        let chord = Chord {
+            frets: frets_from_numbers(&[10, 12, 10, 11, 10, 10]),
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "e|-10-|\nB|-10-|\nG|-11-|\nD|-10-|\nA|-12-|\nE|-10-|"
+        );
+
+        let chord = Chord {
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "e|-x-|\nB|-8-|\nG|-7-|\nD|-6-|\nA|-7-|\nE|-x-|"
+        );
+    }
+
+    #[test]
+    fn should_mark_unplayed_and_optional_strings() {
+        let chord = Chord {
+            frets: vec![
+                Fret::Optional(3),
+                Fret::Fretted(2),
+                Fret::Open,
+                Fret::Open,
+                Fret::Open,
+                Fret::Unplayed,
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "e|-----|\nB|-0---|\nG|-0---|\nD|-0---|\nA|-2---|\nE|-(3)-|"
+        );
+    }
+
+    #[test]
+    fn should_render_progression_with_bar_lines() {
+        let c = Chord {
+            frets: frets_from_numbers(&[-1, 3, 2, 0, 1, 0]),
+            ..Default::default()
+        };
+        let g = Chord {
+            frets: frets_from_numbers(&[3, 2, 0, 0, 0, 3]),
+            ..Default::default()
+        };
+        let d = Chord {
+            frets: frets_from_numbers(&[-1, -1, 0, 2, 3, 2]),
+            ..Default::default()
+        };
+        let f = Chord {
+            frets: frets_from_numbers(&[1, 3, 3, 2, 1, 1]),
+            ..Default::default()
+        };
+
+        let first = [c, g];
+        let second = [d, f];
+        let expected = "e|-0--3-|-2--1-|
+B|-1--0-|-3--1-|
+G|-0--0-|-2--2-|
+D|-2--0-|-0--3-|
+A|-3--2-|-x--3-|
+E|-x--3-|-x--1-|";
+        assert_eq!(tab_staff(&[&first, &second]), expected);
+    }
+
+    #[test]
+    fn should_name_lines_from_tuning() {
+        // drop D
+        let chord = Chord {
+            frets: frets_from_numbers(&[0, 0, 0, 2, 3, 2]),
+            tuning: Tuning(vec![38, 45, 50, 55, 59, 64]),
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "E|-2-|\nB|-3-|\nG|-2-|\nD|-0-|\nA|-0-|\nD|-0-|"
+        );
+
+        // seven strings, and fewer frets than strings
+        let chord = Chord {
+            frets: frets_from_numbers(&[3, 2, 0]),
+            tuning: Tuning(vec![35, 40, 45, 50, 55, 59, 64]),
+            ..Default::default()
+        };
+        assert_eq!(
+            chord.to_tab(),
+            "E|---|\nB|---|\nG|---|\nD|---|\nA|-0-|\nE|-2-|\nB|-3-|"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::types::Fret;
+
+/// Pitch class of a note name, C = 0. Takes ASCII or unicode accidentals,
+/// eg "E♭", "Eb", "F#", "F♯". Anything after the note is ignored.
+pub fn pitch_class(name: &str) -> Option<u8> {
+    let mut chars = name.trim().chars().peekable();
+    let natural: i32 = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+
+    let mut offset = 0;
+    while let Some(accidental) = chars.peek() {
+        match accidental {
+            '♭' | 'b' => offset -= 1,
+            '♯' | '#' => offset += 1,
+            '♮' => (),
+            _ => break,
+        }
+        chars.next();
+    }
+    Some((natural + offset).rem_euclid(12) as u8)
+}
+
+/// Note name and whatever follows it, eg "F#" and "m7"
+pub fn split_note(name: &str) -> (&str, &str) {
+    let name = name.trim();
+    let length = name
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| !matches!(c, '♭' | 'b' | '♯' | '#' | '♮'))
+        .map(|(index, _)| index)
+        .unwrap_or(name.len());
+    name.split_at(length)
+}
+
+/// MIDI note sounding on a string, if it's played
+pub fn string_note(open: u8, fret: &Fret) -> Option<u8> {
+    fret.number().map(|fret| (open as i32 + fret) as u8)
+}
+
+// semitones above the root for each degree of the major scale
+const MAJOR_SCALE: [i32; 7] = [0, 2, 4, 5, 7, 9, 11];
+
+fn parse_degree(degree: &str) -> Option<u8> {
+    let number = degree.trim_start_matches(['♭', 'b', '♯', '#']);
+    let accidentals = &degree[..degree.len() - number.len()];
+    let number: usize = number.parse().ok()?;
+    if number == 0 {
+        return None;
+    }
+
+    let mut semitones = MAJOR_SCALE[(number - 1) % 7];
+    for accidental in accidentals.chars() {
+        match accidental {
+            '♭' | 'b' => semitones -= 1,
+            _ => semitones += 1,
+        }
+    }
+    Some(semitones.rem_euclid(12) as u8)
+}
+
+/// Semitones above the root for a formula of scale degrees, eg
+/// "1 2 ♭3 4 5 ♭6 ♭7". Degrees past 7 wrap round, so 9 is the same as 2.
+pub fn parse_formula(formula: &str) -> Option<Vec<u8>> {
+    let degrees = formula
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|degree| !degree.is_empty())
+        .map(parse_degree)
+        .collect::<Option<Vec<u8>>>()?;
+    (!degrees.is_empty()).then_some(degrees)
+}
+
+fn named_formula(name: &str) -> Option<&'static str> {
+    let formula = match name.trim().to_lowercase().as_str() {
+        "major" | "ionian" => "1 2 3 4 5 6 7",
+        "dorian" => "1 2 ♭3 4 5 6 ♭7",
+        "phrygian" => "1 ♭2 ♭3 4 5 ♭6 ♭7",
+        "lydian" => "1 2 3 ♯4 5 6 7",
+        "mixolydian" => "1 2 3 4 5 6 ♭7",
+        "minor" | "natural minor" | "aeolian" => "1 2 ♭3 4 5 ♭6 ♭7",
+        "locrian" => "1 ♭2 ♭3 4 ♭5 ♭6 ♭7",
+        "harmonic minor" => "1 2 ♭3 4 5 ♭6 7",
+        "melodic minor" => "1 2 ♭3 4 5 6 7",
+        "major pentatonic" => "1 2 3 5 6",
+        "minor pentatonic" => "1 ♭3 4 5 ♭7",
+        "blues" => "1 ♭3 4 ♭5 5 ♭7",
+        // arpeggios
+        "major arpeggio" => "1 3 5",
+        "minor arpeggio" => "1 ♭3 5",
+        "7 arpeggio" => "1 3 5 ♭7",
+        "maj7 arpeggio" => "1 3 5 7",
+        "m7 arpeggio" => "1 ♭3 5 ♭7",
+        "diminished arpeggio" => "1 ♭3 ♭5",
+        "augmented arpeggio" => "1 3 ♯5",
+        _ => return None,
+    };
+    Some(formula)
+}
+
+// qualities chords are named with, most common first
+pub(crate) const CHORD_QUALITIES: [&str; 15] = [
+    "", "m", "7", "m7", "maj7", "sus4", "sus2", "6", "m6", "dim", "aug", "m7♭5", "dim7", "mMaj7",
+    "7sus4",
+];
+
+fn chord_formula(quality: &str) -> Option<&'static str> {
+    let formula = match quality {
+        "" | "maj" | "major" => "1 3 5",
+        "m" | "min" | "minor" | "-" => "1 ♭3 5",
+        "dim" | "o" | "°" => "1 ♭3 ♭5",
+        "aug" | "+" => "1 3 ♯5",
+        "sus2" => "1 2 5",
+        "sus4" | "sus" => "1 4 5",
+        "6" => "1 3 5 6",
+        "m6" => "1 ♭3 5 6",
+        "7" => "1 3 5 ♭7",
+        "maj7" | "M7" | "Δ" | "Δ7" => "1 3 5 7",
+        "m7" | "min7" | "-7" => "1 ♭3 5 ♭7",
+        "m7b5" | "m7♭5" | "ø" | "ø7" => "1 ♭3 ♭5 ♭7",
+        "dim7" | "o7" | "°7" => "1 ♭3 ♭5 6",
+        "mMaj7" | "m(maj7)" | "mM7" => "1 ♭3 5 7",
+        "7sus4" => "1 4 5 ♭7",
+        _ => return None,
+    };
+    Some(formula)
+}
+
+/// Root pitch class and semitones above it for a chord symbol, eg "Am7" or
+/// "B♭maj7"
+pub fn parse_chord(symbol: &str) -> Option<(u8, Vec<u8>)> {
+    let (note, quality) = split_note(symbol);
+    let root = pitch_class(note)?;
+    parse_formula(chord_formula(quality)?).map(|intervals| (root, intervals))
+}
+
+/// Root of a chord title such as "Am7", "E9" or "D/F♯", or None for titles
+/// which aren't chords, like "Chord" or "Blues shape"
+pub(crate) fn title_root(title: &str) -> Option<u8> {
+    let chord = title.split('/').next().unwrap_or(title);
+    if let Some((root, _)) = parse_chord(chord) {
+        return Some(root);
+    }
+    // extended chords such as E9 or Cadd9 aren't in the formulas
+    let (note, quality) = split_note(chord);
+    let extension = quality.trim_start_matches(|c: char| c.is_alphabetic());
+    let word = &quality[..quality.len() - extension.len()];
+    let is_extended = [
+        "", "m", "M", "maj", "min", "add", "madd", "sus", "dim", "aug",
+    ]
+    .contains(&word)
+        && extension.starts_with(|c: char| c.is_ascii_digit());
+    is_extended.then(|| pitch_class(note)).flatten()
+}
+
+/// Letters above the root (0 for the root, 2 for a third) and semitones above
+/// it for each note of a chord quality such as "m7"
+pub(crate) fn chord_degrees(quality: &str) -> Option<Vec<(usize, u8)>> {
+    chord_formula(quality)?
+        .split_whitespace()
+        .map(|degree| {
+            let semitones = parse_degree(degree)?;
+            let number: usize = degree
+                .trim_start_matches(['♭', 'b', '♯', '#'])
+                .parse()
+                .ok()?;
+            Some(((number - 1) % 7, semitones))
+        })
+        .collect()
+}
+
+/// Semitones above the root for a named scale, mode or arpeggio (eg "dorian",
+/// "minor pentatonic", "maj7 arpeggio"), or a formula like "1 ♭3 5"
+pub fn scale_intervals(scale: &str) -> Option<Vec<u8>> {
+    parse_formula(named_formula(scale).unwrap_or(scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        theory::{
+            parse_chord, parse_formula, pitch_class, scale_intervals, string_note, title_root,
+        },
+        types::Fret,
+    };
+
+    #[test]
+    fn should_get_pitch_classes() {
+        assert_eq!(pitch_class("C"), Some(0));
+        assert_eq!(pitch_class("c"), Some(0));
+        assert_eq!(pitch_class("E♭"), Some(3));
+        assert_eq!(pitch_class("Eb"), Some(3));
+        assert_eq!(pitch_class("F#"), Some(6));
+        assert_eq!(pitch_class("F♯"), Some(6));
+        assert_eq!(pitch_class("B♮"), Some(11));
+        assert_eq!(pitch_class("Cb"), Some(11));
+        assert_eq!(pitch_class("B#"), Some(0));
+        assert_eq!(pitch_class("Bbm7"), Some(10));
+        assert_eq!(pitch_class("Hendrix"), None);
+        assert_eq!(pitch_class(""), None);
+    }
+
+    #[test]
+    fn should_get_string_notes() {
+        assert_eq!(string_note(40, &Fret::Open), Some(40));
+        assert_eq!(string_note(40, &Fret::Fretted(3)), Some(43));
+        assert_eq!(string_note(45, &Fret::Optional(2)), Some(47));
+        assert_eq!(string_note(45, &Fret::Muted), None);
+    }
+
+    #[test]
+    fn should_parse_formulas() {
+        assert_eq!(
+            parse_formula("1 2 3 4 5 6 7"),
+            Some(vec![0, 2, 4, 5, 7, 9, 11])
+        );
+        assert_eq!(parse_formula("1, b3, 5, ♭7"), Some(vec![0, 3, 7, 10]));
+        assert_eq!(parse_formula("1 #4 ♯5"), Some(vec![0, 6, 8]));
+        assert_eq!(parse_formula("1 9 11"), Some(vec![0, 2, 5]));
+        assert_eq!(parse_formula("b1"), Some(vec![11]));
+        assert_eq!(parse_formula("1 0"), None);
+        assert_eq!(parse_formula("1 x"), None);
+        assert_eq!(parse_formula(""), None);
+    }
+
+    #[test]
+    fn should_get_scale_intervals() {
+        assert_eq!(scale_intervals("Dorian"), Some(vec![0, 2, 3, 5, 7, 9, 10]));
+        assert_eq!(
+            scale_intervals("minor pentatonic"),
+            Some(vec![0, 3, 5, 7, 10])
+        );
+        assert_eq!(scale_intervals("m7 arpeggio"), Some(vec![0, 3, 7, 10]));
+        assert_eq!(scale_intervals("1 3 5"), Some(vec![0, 4, 7]));
+        assert_eq!(scale_intervals("bebop"), None);
+    }
+
+    #[test]
+    fn should_parse_chords() {
+        assert_eq!(parse_chord("G"), Some((7, vec![0, 4, 7])));
+        assert_eq!(parse_chord("F#m"), Some((6, vec![0, 3, 7])));
+        assert_eq!(parse_chord("B♭maj7"), Some((10, vec![0, 4, 7, 11])));
+        assert_eq!(parse_chord("Bbm7b5"), Some((10, vec![0, 3, 6, 10])));
+        assert_eq!(parse_chord("Am7"), Some((9, vec![0, 3, 7, 10])));
+        assert_eq!(parse_chord("D7"), Some((2, vec![0, 4, 7, 10])));
+        assert_eq!(parse_chord("Cdim7"), Some((0, vec![0, 3, 6, 9])));
+        assert_eq!(parse_chord("G13#11"), None);
+        assert_eq!(parse_chord("Hendrix"), None);
+    }
+
+    #[test]
+    fn should_find_title_roots() {
+        assert_eq!(title_root("Am7"), Some(9));
+        assert_eq!(title_root("D/F♯"), Some(2));
+        assert_eq!(title_root("E9"), Some(4));
+        assert_eq!(title_root("Cadd9"), Some(0));
+        assert_eq!(title_root("B♭m9"), Some(10));
+        // words which happen to start with a note
+        assert_eq!(title_root("Chord"), None);
+        assert_eq!(title_root("Blues shape"), None);
+        assert_eq!(title_root("Add9"), None);
+        assert_eq!(title_root("Hendrix"), None);
+    }
+}
@@ -1,16 +1,226 @@
-use std::hash::Hash;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-#[derive(Hash, Default)]
+#[derive(Default)]
 pub struct Chord<'a> {
-    pub frets: Vec<i32>,       // -1 = skip
-    pub fingers: Vec<&'a str>, // 'x' = skip
+    pub frets: Vec<Fret>,
+    pub fingers: Vec<&'a str>, // only used on fretted strings
     pub title: Option<&'a String>,
     pub hand: Hand,
     pub suffix: Option<&'a String>,
     pub mode: Mode,
     pub use_background: bool,
     pub barres: Option<Vec<i32>>,
+    pub tuning: Tuning,
+    pub strum: Strum,
+    pub show_difficulty: bool,
+    pub manual_barres: bool, // only draw `barres`, don't detect them
+    pub footer: Footer,
+    pub subtitle: Option<&'a String>, // drawn under the diagram
+    pub note_names: bool,             // name the note on each dot
+    pub accidentals: Accidentals,     // how note names are spelled
+    pub naming: NoteNaming,           // how note names are written
+    pub show_spelling: bool,          // notes and intervals under the grid
+    pub show_tuning: bool,            // open string names under the grid
+}
+
+// The hash is used as the output filename, so fields added after 2.1 are
+// only hashed when they've been changed from the default. This keeps the
+// names of existing diagrams stable.
+impl Hash for Chord<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.frets.hash(state);
+        self.fingers.hash(state);
+        self.title.hash(state);
+        self.hand.hash(state);
+        self.suffix.hash(state);
+        self.mode.hash(state);
+        self.use_background.hash(state);
+        self.barres.hash(state);
+
+        if self.tuning != Tuning::default() {
+            self.tuning.hash(state);
+        }
+        if self.strum != Strum::default() {
+            self.strum.hash(state);
+        }
+        if self.show_difficulty {
+            self.show_difficulty.hash(state);
+        }
+        if self.manual_barres {
+            self.manual_barres.hash(state);
+        }
+        if self.footer != Footer::default() {
+            self.footer.hash(state);
+        }
+        if self.subtitle.is_some() {
+            self.subtitle.hash(state);
+        }
+        if self.note_names {
+            self.note_names.hash(state);
+        }
+        if self.accidentals != Accidentals::default() {
+            self.accidentals.hash(state);
+        }
+        if self.naming != NoteNaming::default() {
+            self.naming.hash(state);
+        }
+        if self.show_spelling {
+            self.show_spelling.hash(state);
+        }
+        if self.show_tuning {
+            self.show_tuning.hash(state);
+        }
+    }
+}
+
+/// Notes of a scale, mode or arpeggio across part of the neck
+#[derive(Hash)]
+pub struct Scale<'a> {
+    pub title: Option<&'a String>,
+    pub root: u8,                   // pitch class, C = 0
+    pub intervals: Vec<u8>,         // semitones above the root
+    pub frets: RangeInclusive<i32>, // starting at 0 shows open strings
+    pub tuning: Tuning,
+    pub hand: Hand,
+    pub mode: Mode,
+    pub use_background: bool,
+    pub footer: Footer,
+}
+
+impl Default for Scale<'_> {
+    fn default() -> Self {
+        Scale {
+            title: None,
+            root: 0,
+            intervals: vec![0, 2, 4, 5, 7, 9, 11],
+            frets: position(0),
+            tuning: Tuning::default(),
+            hand: Hand::default(),
+            mode: Mode::default(),
+            use_background: false,
+            footer: Footer::default(),
+        }
+    }
+}
+
+/// Notes across the whole neck, drawn sideways, with one chord shape picked out
+#[derive(Hash, Default)]
+pub struct Neck<'a> {
+    pub scale: Scale<'a>, // notes to show, on frets 0 to 12-24
+    pub shape: Option<Chord<'a>>,
+}
+
+/// Frets for a position: the same five frets as a chord diagram, from `fret`
+pub fn position(fret: i32) -> RangeInclusive<i32> {
+    fret..=fret + 4
+}
+
+/// What's played on each string
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Fret {
+    Muted,    // drawn as an X
+    Unplayed, // nothing drawn
+    Open,
+    Fretted(i32),
+    Optional(i32), // can be left out, 0 = optional open string
+}
+
+impl Fret {
+    /// Fret number for strings which sound, 0 for open
+    pub fn number(&self) -> Option<i32> {
+        match self {
+            Fret::Open => Some(0),
+            Fret::Fretted(fret) | Fret::Optional(fret) => Some(*fret),
+            Fret::Muted | Fret::Unplayed => None,
+        }
+    }
+
+    /// Fret number for strings which need a finger
+    pub fn fretted(&self) -> Option<i32> {
+        self.number().filter(|fret| *fret > 0)
+    }
+}
+
+// Hashed as the numbers used in 2.1 so existing filenames don't change
+impl Hash for Fret {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Fret::Muted => (-1).hash(state),
+            Fret::Open => 0.hash(state),
+            Fret::Fretted(fret) => fret.hash(state),
+            // newer states each take their own tag below any fret
+            Fret::Unplayed => i32::MIN.hash(state),
+            Fret::Optional(fret) => {
+                (i32::MIN + 1).hash(state);
+                fret.hash(state);
+            }
+        }
+    }
+}
+
+/// -1 for muted, as used in 2.1
+impl From<i32> for Fret {
+    fn from(value: i32) -> Self {
+        match value {
+            fret if fret < 0 => Fret::Muted,
+            0 => Fret::Open,
+            fret => Fret::Fretted(fret),
+        }
+    }
+}
+
+/// Converts numbers with -1 for muted strings
+pub fn frets_from_numbers(numbers: &[i32]) -> Vec<Fret> {
+    numbers.iter().map(|number| Fret::from(*number)).collect()
+}
+
+/// `x` or -1 muted, `-` unplayed, brackets for optional eg `(2)`
+impl FromStr for Fret {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "x" | "X" => Ok(Fret::Muted),
+            "-" => Ok(Fret::Unplayed),
+            _ if s.starts_with('(') && s.ends_with(')') => s[1..s.len() - 1]
+                .parse::<i32>()
+                .map(|fret| Fret::Optional(fret.max(0)))
+                .map_err(|_| ()),
+            _ => s.parse::<i32>().map(Fret::from).map_err(|_| ()),
+        }
+    }
+}
+
+impl fmt::Display for Fret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Fret::Muted => write!(f, "x"),
+            Fret::Unplayed => write!(f, "-"),
+            Fret::Open => write!(f, "0"),
+            Fret::Fretted(fret) => write!(f, "{}", fret),
+            Fret::Optional(fret) => write!(f, "({})", fret),
+        }
+    }
+}
+
+// written the same way as on the command line
+impl Serialize for Fret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fret = String::deserialize(deserializer)?;
+        fret.parse::<Fret>()
+            .map_err(|_| serde::de::Error::custom(format!("invalid fret `{}`", fret)))
+    }
 }
 
 #[derive(Debug)]
@@ -39,14 +249,17 @@ impl From<usize> for GuitarString {
 pub const LIGHT_COLOUR: &str = "#FBF6E2";
 pub const DARK_COLOUR: &str = "#160c1c";
 
-#[derive(Hash, Default, Copy, Clone)]
+#[derive(PartialEq, Hash, Default, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Mode {
     #[default]
     Light,
     Dark,
+    Auto, // follows the reader's light or dark preference
 }
 
-#[derive(PartialEq, Hash, Default)]
+#[derive(PartialEq, Hash, Default, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Hand {
     #[default]
     Right,
@@ -63,3 +276,173 @@ impl FromStr for Hand {
         }
     }
 }
+
+/// Accidentals used when naming notes. `Auto` spells them from the key or
+/// chord, so B♭ major has E♭ rather than D♯.
+#[derive(PartialEq, Hash, Default, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Accidentals {
+    #[default]
+    Auto,
+    Sharps,
+    Flats,
+}
+
+impl FromStr for Accidentals {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sharps" | "sharp" | "#" | "♯" => Ok(Accidentals::Sharps),
+            "flats" | "flat" | "b" | "♭" => Ok(Accidentals::Flats),
+            "auto" => Ok(Accidentals::Auto),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How note names are written. Nashville numbers count up from a tonic,
+/// given as a pitch class.
+#[derive(PartialEq, Hash, Default, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteNaming {
+    #[default]
+    English, // C D E F G A B
+    German,  // H for B, B for B♭, and Fis, Es
+    Solfege, // fixed do: Do Re Mi Fa Sol La Si
+    Nashville(u8),
+}
+
+/// Nashville numbers start from C until they're given a tonic
+impl FromStr for NoteNaming {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "english" => Ok(NoteNaming::English),
+            "german" => Ok(NoteNaming::German),
+            "solfege" | "solfège" => Ok(NoteNaming::Solfege),
+            "nashville" => Ok(NoteNaming::Nashville(0)),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Open string pitches as MIDI note numbers, low E first
+#[derive(Hash, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Tuning(pub Vec<u8>);
+
+impl Default for Tuning {
+    fn default() -> Self {
+        // E2 A2 D3 G3 B3 E4
+        Tuning(vec![40, 45, 50, 55, 59, 64])
+    }
+}
+
+#[derive(PartialEq, Hash, Default, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StrumDirection {
+    #[default]
+    Block,
+    Down,
+    Up,
+}
+
+impl FromStr for StrumDirection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "down" => Ok(StrumDirection::Down),
+            "up" => Ok(StrumDirection::Up),
+            _ => Ok(StrumDirection::Block),
+        }
+    }
+}
+
+#[derive(PartialEq, Hash, Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Strum {
+    pub direction: StrumDirection,
+    pub delay: u32, // ms between strings
+    pub tempo: u32, // bpm, chord rings for one bar
+}
+
+impl Default for Strum {
+    fn default() -> Self {
+        Strum {
+            direction: StrumDirection::Block,
+            delay: 0,
+            tempo: 120,
+        }
+    }
+}
+
+/// Text along the bottom of the diagram
+#[derive(PartialEq, Hash, Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Footer {
+    #[default]
+    Default, // chordgenerator.xyz
+    Text(String),
+    Link {
+        text: String,
+        href: String,
+    },
+    None, // diagram is cropped to fit
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{frets_from_numbers, Fret},
+        utils::get_filename,
+    };
+
+    #[test]
+    fn should_parse_frets() {
+        assert_eq!("x".parse::<Fret>(), Ok(Fret::Muted));
+        assert_eq!("-1".parse::<Fret>(), Ok(Fret::Muted));
+        assert_eq!("-".parse::<Fret>(), Ok(Fret::Unplayed));
+        assert_eq!("0".parse::<Fret>(), Ok(Fret::Open));
+        assert_eq!("12".parse::<Fret>(), Ok(Fret::Fretted(12)));
+        assert_eq!("(3)".parse::<Fret>(), Ok(Fret::Optional(3)));
+        assert_eq!("(0)".parse::<Fret>(), Ok(Fret::Optional(0)));
+        assert_eq!("?".parse::<Fret>(), Err(()));
+    }
+
+    #[test]
+    fn should_write_frets() {
+        for fret in ["x", "-", "0", "12", "(3)", "(0)"] {
+            assert_eq!(fret.parse::<Fret>().unwrap().to_string(), fret);
+        }
+    }
+
+    #[test]
+    fn should_convert_numbers() {
+        assert_eq!(
+            frets_from_numbers(&[-1, 0, 2]),
+            vec![Fret::Muted, Fret::Open, Fret::Fretted(2)]
+        );
+    }
+
+    #[test]
+    fn should_get_fret_numbers() {
+        assert_eq!(Fret::Muted.number(), None);
+        assert_eq!(Fret::Unplayed.number(), None);
+        assert_eq!(Fret::Open.number(), Some(0));
+        assert_eq!(Fret::Optional(0).number(), Some(0));
+        assert_eq!(Fret::Optional(0).fretted(), None);
+        assert_eq!(Fret::Optional(4).fretted(), Some(4));
+        assert_eq!(Fret::Fretted(4).fretted(), Some(4));
+    }
+
+    #[test]
+    fn should_hash_string_states_apart() {
+        assert_eq!(get_filename(&Fret::Fretted(3)), get_filename(&3));
+        assert_ne!(
+            get_filename(&(Fret::Unplayed, Fret::Fretted(3))),
+            get_filename(&Fret::Optional(3))
+        );
+    }
+}
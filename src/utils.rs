@@ -1,7 +1,75 @@
+use serde::Serialize;
 use std::hash::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
-use crate::types::{Chord, GuitarString, Mode, DARK_COLOUR, LIGHT_COLOUR};
+use crate::types::{
+    Chord, Footer, Fret, GuitarString, Hand, Mode, Strum, StrumDirection, DARK_COLOUR, LIGHT_COLOUR,
+};
+
+const STRING_COUNT: usize = 6;
+const FRET_COUNT: i32 = 5; // frets shown on the diagram
+const OFFSET_LEFT: i32 = 50;
+const OFFSET_TOP: i32 = 50;
+
+pub const FOOTER_HEIGHT: i32 = 30;
+
+pub const SUBTITLE_HEIGHT: i32 = 20;
+
+pub const SPELLING_HEIGHT: i32 = 40;
+
+pub const TUNING_HEIGHT: i32 = 20;
+
+pub const WIDTH: i32 = 300;
+
+/// Diagram height, without the space for the footer if there isn't one
+pub fn get_height(footer: &Footer) -> i32 {
+    match footer {
+        Footer::None => 310 - FOOTER_HEIGHT,
+        _ => 310,
+    }
+}
+
+/// Height of a chord diagram, with room for its subtitle, tuning and
+/// spelling panel
+pub fn get_chord_height(chord: &Chord) -> i32 {
+    let mut height = get_height(&chord.footer);
+    if chord.subtitle.is_some() {
+        height += SUBTITLE_HEIGHT;
+    }
+    if chord.show_tuning {
+        height += TUNING_HEIGHT;
+    }
+    if chord.show_spelling {
+        height += SPELLING_HEIGHT;
+    }
+    height
+}
+
+/// Height of a diagram showing `frets` frets rather than the usual five
+pub fn get_neck_height(frets: i32, string_space: &i32, footer: &Footer) -> i32 {
+    get_height(footer) + (frets - FRET_COUNT) * string_space
+}
+
+/// Maps strings to where they're drawn. Everything placed on a string goes
+/// through this so left-handed diagrams are mirrored consistently.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Transform {
+    pub hand: Hand,
+}
+
+impl Transform {
+    pub fn new(hand: Hand) -> Self {
+        Transform { hand }
+    }
+
+    /// Column a string is drawn in, where string 0 is low E
+    pub fn string(&self, string: usize) -> GuitarString {
+        match self.hand {
+            Hand::Right => string.into(),
+            Hand::Left => (STRING_COUNT - 1 - string.min(STRING_COUNT - 1)).into(),
+        }
+    }
+}
 
 pub fn get_note_coords(
     note: &i32,
@@ -9,19 +77,64 @@ pub fn get_note_coords(
     string_space: &i32,
     min_fret: &i32,
 ) -> (i32, i32) {
-    let offset_left = 50;
-    let offset_top = 50;
-
     let mut offset_fret = *note;
     if min_fret > &1 {
         offset_fret = (note - min_fret) + 2; // 1=first playable pos
     }
 
-    let x = offset_left + string as i32 * string_space;
-    let y = offset_fret * string_space + offset_top - (string_space / 2); // fret
+    let x = OFFSET_LEFT + string as i32 * string_space;
+    let y = offset_fret * string_space + OFFSET_TOP - (string_space / 2); // fret
     (x, y)
 }
 
+/// Vertical line for a string, passed to templates
+#[derive(PartialEq, Debug, Serialize)]
+pub struct StringLine {
+    pub x: i32,
+    pub top: i32,
+    pub bottom: i32,
+}
+
+/// Horizontal line for a fret, passed to templates. The first is the nut.
+#[derive(PartialEq, Debug, Serialize)]
+pub struct FretLine {
+    pub y: i32,
+    pub left: i32,
+    pub right: i32,
+}
+
+/// Fretted note, passed to templates
+#[derive(PartialEq, Debug, Serialize)]
+pub struct Dot {
+    pub x: i32,
+    pub y: i32,
+    pub string: usize, // 0 = low E, before mirroring
+    pub fret: i32,
+    pub finger: String,
+    pub optional: bool,
+    pub note: String, // eg "F♯", spelled from the chord
+}
+
+pub fn get_string_lines(string_space: &i32) -> Vec<StringLine> {
+    (0..STRING_COUNT as i32)
+        .map(|string| StringLine {
+            x: OFFSET_LEFT + string * string_space,
+            top: OFFSET_TOP,
+            bottom: OFFSET_TOP + FRET_COUNT * string_space,
+        })
+        .collect()
+}
+
+pub fn get_fret_lines(string_space: &i32) -> Vec<FretLine> {
+    (0..=FRET_COUNT)
+        .map(|fret| FretLine {
+            y: OFFSET_TOP + fret * string_space,
+            left: OFFSET_LEFT,
+            right: OFFSET_LEFT + (STRING_COUNT as i32 - 1) * string_space,
+        })
+        .collect()
+}
+
 #[derive(PartialEq, Debug)]
 pub struct Palette<'a> {
     pub fg: &'a str,
@@ -30,7 +143,8 @@ pub struct Palette<'a> {
 
 pub fn get_palette<'a>(mode: Mode) -> Palette<'a> {
     match mode {
-        Mode::Light => Palette {
+        // Auto is drawn light, its stylesheet switches to dark
+        Mode::Light | Mode::Auto => Palette {
             fg: DARK_COLOUR,
             bg: LIGHT_COLOUR,
         },
@@ -41,34 +155,62 @@ pub fn get_palette<'a>(mode: Mode) -> Palette<'a> {
     }
 }
 
-pub fn find_all(frets: &[i32], search: &i32) -> Vec<usize> {
+pub fn find_all(frets: &[Fret], search: &i32) -> Vec<usize> {
     frets
         .iter()
         .enumerate()
         .filter(|(index, fret)| {
+            let fret = fret.number();
             // the E9 check!
             // does next fret exist and is played?
-            if index + 1 < frets.len() && frets[index + 1] != -1 {
+            if let Some(next) = frets.get(index + 1).and_then(|next| next.number()) {
                 // is next fret higher or eq?
-                return *fret == search && frets[index + 1] >= **fret;
+                return fret == Some(*search) && next >= *search;
             }
-            *fret == search
+            fret == Some(*search)
         })
         .map(|(index, _)| index)
         .collect::<Vec<_>>()
 }
 
-pub fn get_filename(chord: &Chord) -> u64 {
+/// Start time of each played string in ms, low E first
+pub fn get_strum_offsets(strum: &Strum, note_count: usize) -> Vec<u64> {
+    let delay = u64::from(strum.delay);
+    (0..note_count as u64)
+        .map(|i| match strum.direction {
+            StrumDirection::Block => 0,
+            StrumDirection::Down => i.saturating_mul(delay),
+            StrumDirection::Up => (note_count as u64 - 1 - i).saturating_mul(delay),
+        })
+        .collect()
+}
+
+/// Escapes text placed inside SVG elements or attributes
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn get_filename<T: Hash>(diagram: &T) -> u64 {
     let mut s = DefaultHasher::new();
-    chord.hash(&mut s);
+    diagram.hash(&mut s);
     s.finish()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        types::{Chord, GuitarString, Hand, Mode, DARK_COLOUR, LIGHT_COLOUR},
-        utils::{find_all, get_filename, get_note_coords, get_palette, Palette},
+        types::{
+            frets_from_numbers, Chord, Footer, GuitarString, Hand, Mode, Strum, StrumDirection,
+            DARK_COLOUR, LIGHT_COLOUR,
+        },
+        utils::{
+            escape_xml, find_all, get_chord_height, get_filename, get_fret_lines, get_height,
+            get_note_coords, get_palette, get_string_lines, get_strum_offsets, FretLine, Palette,
+            StringLine, Transform,
+        },
     };
 
     #[test]
@@ -84,6 +226,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_crop_without_footer() {
+        assert_eq!(get_height(&Footer::Default), 310);
+        assert_eq!(get_height(&Footer::Text(String::from("Example Co."))), 310);
+        assert_eq!(get_height(&Footer::None), 280);
+    }
+
+    #[test]
+    fn should_make_room_for_subtitle_and_spelling() {
+        let subtitle = String::from("Root position");
+        let mut chord = Chord {
+            subtitle: Some(&subtitle),
+            ..Default::default()
+        };
+        assert_eq!(get_chord_height(&chord), 330);
+        chord.show_spelling = true;
+        assert_eq!(get_chord_height(&chord), 370);
+        chord.show_tuning = true;
+        assert_eq!(get_chord_height(&chord), 390);
+        chord.footer = Footer::None;
+        chord.subtitle = None;
+        assert_eq!(get_chord_height(&chord), 340);
+    }
+
+    #[test]
+    fn should_get_grid_lines() {
+        let strings = get_string_lines(&40);
+        assert_eq!(strings.len(), 6);
+        assert_eq!(
+            strings[0],
+            StringLine {
+                x: 50,
+                top: 50,
+                bottom: 250
+            }
+        );
+        assert_eq!(strings[5].x, 250);
+
+        let frets = get_fret_lines(&40);
+        assert_eq!(frets.len(), 6);
+        assert_eq!(
+            frets[0],
+            FretLine {
+                y: 50,
+                left: 50,
+                right: 250
+            }
+        );
+        assert_eq!(frets[5].y, 250);
+    }
+
+    #[test]
+    fn should_mirror_strings_for_left_hand() {
+        let right = Transform::new(Hand::Right);
+        let left = Transform::new(Hand::Left);
+        for string in 0..6 {
+            assert_eq!(right.string(string) as usize, string);
+            assert_eq!(left.string(string) as usize, 5 - string);
+        }
+    }
+
     #[test]
     fn shoud_get_palette() {
         assert_eq!(
@@ -93,6 +296,7 @@ mod tests {
                 bg: LIGHT_COLOUR
             }
         );
+        assert_eq!(get_palette(Mode::Auto), get_palette(Mode::Light));
         assert_eq!(
             get_palette(Mode::Dark),
             Palette {
@@ -104,14 +308,45 @@ mod tests {
 
     #[test]
     fn check_find_all() {
-        assert_eq!(find_all(&vec![2, 1, 3], &1), vec![1]);
+        assert_eq!(find_all(&frets_from_numbers(&[2, 1, 3]), &1), vec![1]);
         let empty_expected: Vec<usize> = vec![];
-        assert_eq!(find_all(&vec![2, 2, 2], &1), empty_expected);
-        assert_eq!(find_all(&vec![2, 2, 2], &2), vec![0, 1, 2]);
-        assert_eq!(find_all(&vec![2, 2, 2, 3, 4, 2], &2), vec![0, 1, 2, 5]);
+        assert_eq!(
+            find_all(&frets_from_numbers(&[2, 2, 2]), &1),
+            empty_expected
+        );
+        assert_eq!(find_all(&frets_from_numbers(&[2, 2, 2]), &2), vec![0, 1, 2]);
+        assert_eq!(
+            find_all(&frets_from_numbers(&[2, 2, 2, 3, 4, 2]), &2),
+            vec![0, 1, 2, 5]
+        );
 
         // not if there's a lower neighbour later on - E9
-        assert_eq!(find_all(&vec![2, 1, 2, 2, 4, 2], &2), vec![2, 3, 5]);
+        assert_eq!(
+            find_all(&frets_from_numbers(&[2, 1, 2, 2, 4, 2]), &2),
+            vec![2, 3, 5]
+        );
+    }
+
+    #[test]
+    fn should_offset_strummed_notes() {
+        let mut strum = Strum::default();
+        assert_eq!(get_strum_offsets(&strum, 5), vec![0, 0, 0, 0, 0]);
+
+        strum.direction = StrumDirection::Down;
+        strum.delay = 20;
+        assert_eq!(get_strum_offsets(&strum, 5), vec![0, 20, 40, 60, 80]);
+
+        strum.direction = StrumDirection::Up;
+        assert_eq!(get_strum_offsets(&strum, 5), vec![80, 60, 40, 20, 0]);
+    }
+
+    #[test]
+    fn should_escape_xml() {
+        assert_eq!(escape_xml("A♭m"), "A♭m");
+        assert_eq!(
+            escape_xml("<Tom & \"Jerry\">"),
+            "&lt;Tom &amp; &quot;Jerry&quot;&gt;"
+        );
     }
 
     #[test]
@@ -119,7 +354,7 @@ mod tests {
         let title = String::from("");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, -1, -1, -1, -1, -1],
+            frets: frets_from_numbers(&[-1, -1, -1, -1, -1, -1]),
             fingers: vec!["x", "x", "x", "x", "x", "x"],
             hand: Hand::Right,
             ..Default::default()
@@ -130,7 +365,7 @@ mod tests {
         let title = String::from("Hendrix♮");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 7, 6, 7, 8, -1],
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
             fingers: vec!["x", "2", "1", "3", "4", "x"],
             ..Default::default()
         };
@@ -140,7 +375,7 @@ mod tests {
         let title = String::from("Hendrix");
         let chord = Chord {
             title: Some(&title),
-            frets: vec![-1, 7, 6, 7, 8, -1],
+            frets: frets_from_numbers(&[-1, 7, 6, 7, 8, -1]),
             fingers: vec!["x", "2", "1", "3", "4", "x"],
             hand: Hand::Left,
             ..Default::default()
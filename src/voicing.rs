@@ -0,0 +1,231 @@
+use crate::fingering::suggest_fingering;
+use crate::types::{Chord, Fret, Tuning};
+
+/// How the notes of a close chord are spread across the strings
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Voicing {
+    Close,
+    Drop2, // second highest note down an octave
+    Drop3, // third highest note down an octave
+}
+
+/// A chord played on one set of strings, with a chord tone in the bass
+#[derive(PartialEq, Debug)]
+pub struct Inversion {
+    pub inversion: usize, // 0 = root position, 1 = third in the bass, ...
+    pub frets: Vec<Fret>,
+    pub fingers: Vec<&'static str>,
+    pub barres: Vec<i32>,
+}
+
+impl Inversion {
+    /// "Root position", "1st inversion", ...
+    pub fn label(&self) -> String {
+        match self.inversion {
+            0 => String::from("Root position"),
+            1 => String::from("1st inversion"),
+            2 => String::from("2nd inversion"),
+            3 => String::from("3rd inversion"),
+            inversion => format!("{}th inversion", inversion),
+        }
+    }
+
+    pub fn chord<'a>(
+        &self,
+        title: Option<&'a String>,
+        suffix: Option<&'a String>,
+        subtitle: Option<&'a String>,
+    ) -> Chord<'a> {
+        Chord {
+            frets: self.frets.clone(),
+            fingers: self.fingers.clone(),
+            title,
+            suffix,
+            subtitle,
+            barres: Some(self.barres.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+// semitones above the root, low to high, stacked up from the chord tone
+// `bass` then spread out
+fn stack(intervals: &[u8], bass: usize, voicing: Voicing) -> Vec<i32> {
+    let mut notes: Vec<i32> = vec![];
+    for i in 0..intervals.len() {
+        let mut note = intervals[(bass + i) % intervals.len()] as i32;
+        while notes.last().is_some_and(|last| note <= *last) {
+            note += 12;
+        }
+        notes.push(note);
+    }
+
+    let dropped = match voicing {
+        Voicing::Close => None,
+        Voicing::Drop2 => notes.len().checked_sub(2),
+        Voicing::Drop3 => notes.len().checked_sub(3),
+    };
+    if let Some(dropped) = dropped {
+        notes[dropped] -= 12;
+        notes.sort();
+    }
+    notes
+}
+
+/// Every inversion of a chord on a set of strings (low E = 0), one note per
+/// string, in the lowest place each fits on the neck. `intervals` are
+/// semitones above the root, as from `parse_chord`. Returns nothing if the
+/// number of strings doesn't match the chord, or a drop voicing needs more
+/// notes.
+pub fn inversions(
+    root: u8,
+    intervals: &[u8],
+    voicing: Voicing,
+    strings: &[usize],
+    tuning: &Tuning,
+) -> Vec<Inversion> {
+    let minimum = match voicing {
+        Voicing::Close => 1,
+        Voicing::Drop2 => 3,
+        Voicing::Drop3 => 4,
+    };
+    let mut strings = strings.to_vec();
+    strings.sort();
+    strings.dedup();
+    if intervals.len() < minimum
+        || strings.len() != intervals.len()
+        || strings.iter().any(|string| *string >= tuning.0.len())
+    {
+        return vec![];
+    }
+
+    let mut intervals = intervals.to_vec();
+    intervals.sort();
+
+    // shapes which need more than four fingers are left out
+    let mut inversions: Vec<Inversion> = (0..intervals.len())
+        .filter_map(|close| {
+            // dropping a note can put a different chord tone in the bass
+            let notes = stack(&intervals, close, voicing);
+            let inversion = intervals
+                .iter()
+                .position(|interval| *interval as i32 == notes[0].rem_euclid(12))
+                .unwrap_or(close);
+
+            let low_string = tuning.0[strings[0]] as i32;
+            let bass_fret = (root as i32 + notes[0] - low_string).rem_euclid(12);
+            let mut numbers: Vec<i32> = strings
+                .iter()
+                .zip(notes.iter())
+                .map(|(string, note)| {
+                    low_string + bass_fret + note - notes[0] - tuning.0[*string] as i32
+                })
+                .collect();
+            while numbers.iter().any(|fret| *fret < 0) {
+                numbers.iter_mut().for_each(|fret| *fret += 12);
+            }
+
+            let mut frets = vec![Fret::Muted; tuning.0.len()];
+            for (string, fret) in strings.iter().zip(numbers.iter()) {
+                frets[*string] = Fret::from(*fret);
+            }
+            let fingering = suggest_fingering(&frets)?;
+            Some(Inversion {
+                inversion,
+                frets,
+                fingers: fingering.fingers,
+                barres: fingering.barres,
+            })
+        })
+        .collect();
+    inversions.sort_by_key(|inversion| inversion.inversion);
+    inversions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        theory::parse_chord,
+        types::{frets_from_numbers, Tuning},
+        voicing::{inversions, Voicing},
+    };
+
+    #[test]
+    fn should_find_drop_2_inversions() {
+        // Cmaj7 on D-G-B-E
+        let (root, intervals) = parse_chord("Cmaj7").unwrap();
+        let found = inversions(
+            root,
+            &intervals,
+            Voicing::Drop2,
+            &[2, 3, 4, 5],
+            &Tuning::default(),
+        );
+        let frets: Vec<_> = found
+            .iter()
+            .map(|inversion| inversion.frets.clone())
+            .collect();
+        assert_eq!(
+            frets,
+            vec![
+                frets_from_numbers(&[-1, -1, 10, 12, 12, 12]), // C G B E
+                frets_from_numbers(&[-1, -1, 2, 4, 1, 3]),     // E B C G
+                frets_from_numbers(&[-1, -1, 5, 5, 5, 7]),     // G C E B
+                frets_from_numbers(&[-1, -1, 9, 9, 8, 8]),     // B E G C
+            ]
+        );
+        assert_eq!(found[1].label(), "1st inversion");
+        assert_eq!(found[1].fingers, vec!["x", "x", "2", "4", "1", "3"]);
+    }
+
+    #[test]
+    fn should_find_drop_3_inversions() {
+        // Am7 on E-D-G-B, skipping the A string
+        let (root, intervals) = parse_chord("Am7").unwrap();
+        let found = inversions(
+            root,
+            &intervals,
+            Voicing::Drop3,
+            &[0, 2, 3, 4],
+            &Tuning::default(),
+        );
+        assert_eq!(found.len(), 4);
+        assert_eq!(found[0].frets, frets_from_numbers(&[5, -1, 5, 5, 5, -1]));
+        assert_eq!(found[0].fingers, vec!["1", "x", "2", "3", "4", "x"]);
+        assert_eq!(found[0].label(), "Root position");
+    }
+
+    #[test]
+    fn should_find_close_triads() {
+        // G on G-B-E
+        let (root, intervals) = parse_chord("G").unwrap();
+        let found = inversions(
+            root,
+            &intervals,
+            Voicing::Close,
+            &[3, 4, 5],
+            &Tuning::default(),
+        );
+        let frets: Vec<_> = found
+            .iter()
+            .map(|inversion| inversion.frets.clone())
+            .collect();
+        assert_eq!(
+            frets,
+            vec![
+                frets_from_numbers(&[-1, -1, -1, 12, 12, 10]), // G B D
+                frets_from_numbers(&[-1, -1, -1, 4, 3, 3]),    // B D G
+                frets_from_numbers(&[-1, -1, -1, 7, 8, 7]),    // D G B
+            ]
+        );
+    }
+
+    #[test]
+    fn should_need_a_string_for_each_note() {
+        let (root, intervals) = parse_chord("Cmaj7").unwrap();
+        let tuning = Tuning::default();
+        assert!(inversions(root, &intervals, Voicing::Drop2, &[3, 4, 5], &tuning).is_empty());
+        assert!(inversions(root, &[0, 4, 7], Voicing::Drop3, &[3, 4, 5], &tuning).is_empty());
+        assert!(inversions(root, &intervals, Voicing::Close, &[3, 4, 5, 6], &tuning).is_empty());
+    }
+}